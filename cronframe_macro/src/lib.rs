@@ -1,166 +1,159 @@
 //! Macros for [CronFrame](https://crates.io/crates/cronframe)
 
 use proc_macro::*;
-use quote::{format_ident, quote, ToTokens};
-use syn::{self, parse_macro_input, punctuated::Punctuated, ItemFn, ItemImpl, ItemStruct, Meta};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use std::str::FromStr;
+use syn::{
+    self, parse_macro_input, punctuated::Punctuated, spanned::Spanned, ItemFn, ItemImpl,
+    ItemStruct, Meta,
+};
 
 /// Global Job definition Macro
 #[proc_macro_attribute]
 pub fn cron(att: TokenStream, code: TokenStream) -> TokenStream {
     let args = parse_macro_input!(att with Punctuated::<Meta, syn::Token![,]>::parse_terminated);
+    global_job(args, code.clone().into())
+        .unwrap_or_else(|error| job_error(error, code.into(), None))
+        .into()
+}
 
-    let args = args.into_iter().map(|x| {
-        x.require_name_value()
-            .map(|x| {
-                let arg_name = x.path.to_token_stream().to_string();
-                let arg_val = x.value.to_token_stream().to_string();
-                (arg_name, arg_val.replace("\"", ""))
-            })
-            .unwrap()
-    });
+// the code of the cron macro, misuses are reported as errors spanned on the faulty tokens
+fn global_job(
+    args: Punctuated<Meta, syn::Token![,]>,
+    code: proc_macro2::TokenStream,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let parsed = syn::parse2::<ItemFn>(code.clone())
+        .map_err(|_| syn::Error::new_spanned(&code, "#[cron] can only be applied to a function"))?;
 
-    // should contain ("expr", "* * * * * *")
-    let (arg_1_name, cron_expr) = args.clone().peekable().nth(0).unwrap();
+    let args = job_args(&args)?;
 
-    // should contain ("timeout", "u64")
-    let (arg_2_name, timeout) = args.peekable().nth(1).unwrap();
+    // expr = "* * * * * *", every = "90s" or delay = "10m" and an optional timeout = "time in ms"
+    let trigger_arg = trigger_arg(&args, "cron")?;
+    let timeout_arg = find_arg(&args, "timeout");
+    check_job_args(trigger_arg, timeout_arg)?;
 
-    if arg_1_name == "expr" && arg_2_name == "timeout" {
-        let parsed = syn::parse::<ItemFn>(code.clone());
+    let options = job_options(&args)?;
 
-        if parsed.is_ok() {
-            let origin_function = parsed.clone().unwrap().to_token_stream();
-            let ident = parsed.clone().unwrap().sig.ident;
-            let job_name = ident.to_string();
+    let cron_expr = trigger_expr(trigger_arg)?.unwrap_or_else(|| trigger_arg.value.clone());
+    let timeout = timeout_arg.map_or("0", |arg| arg.value.trim());
 
-            let new_code = quote! {
-                // original function
-                #origin_function
+    let ident = &parsed.sig.ident;
+    let job_name = job_name(&args, ident)?;
 
-                // necessary for automatic job collection
-                cronframe::submit! {
-                    cronframe::JobBuilder::global_job(#job_name, #ident, #cron_expr, #timeout)
-                }
-            };
+    let (job, wrapper) = job_fn(&parsed.sig, quote! {});
 
-            return new_code.into();
-        } else if let Some(error) = parsed.err() {
-            println!("parse Error: {}", error);
+    Ok(quote! {
+        // original function
+        #parsed
+
+        #wrapper
+
+        // necessary for automatic job collection
+        cronframe::submit! {
+            cronframe::JobBuilder::Global {
+                name: #job_name,
+                job: #job,
+                trigger: cronframe::JobTrigger::expr(#cron_expr),
+                timeout: #timeout,
+                options: #options,
+            }
         }
-    }
-    code
+    })
 }
 
 /// Cron Object definition Macro
 #[proc_macro_attribute]
 pub fn cron_obj(_att: TokenStream, code: TokenStream) -> TokenStream {
-    let item_struct = syn::parse::<ItemStruct>(code.clone()).unwrap();
-    let r#struct = item_struct.to_token_stream();
-    let ident_upper = format_ident!("{}", item_struct.ident.clone().to_string().to_uppercase());
-    let struct_name = item_struct.ident;
-    let method_jobs = format_ident!("CRONFRAME_METHOD_JOBS_{}", ident_upper);
-    let function_jobs = format_ident!("CRONFRAME_FUNCTION_JOBS_{}", ident_upper);
-    let cf_fn_jobs_flag = format_ident!("CF_FN_JOBS_FLAG_{}", ident_upper);
-    let cf_fn_jobs_channels = format_ident!("CF_FN_JOBS_CHANNELS_{}", ident_upper);
-
-    // inject the tx field for the drop of method jobs
-    // this requires that the last field in the original struct is followed by a ,
-    let struct_edited: proc_macro2::TokenStream = {
-        let mut tmp = r#struct.to_string();
-        if tmp.contains("{") {
-            tmp.insert_str(
-                tmp.chars().count() - 1,
-                "tx: Option<cronframe::Sender<cronframe::SchedulerMessage>>",
-            );
-        } else {
-            tmp.insert_str(
-                tmp.chars().count() - 1,
-                "{tx: Option<cronframe::Sender<cronframe::SchedulerMessage>>}",
-            );
-            tmp = (&tmp[0..tmp.len() - 1].to_string()).clone();
+    let mut item_struct = match syn::parse::<ItemStruct>(code.clone()) {
+        Ok(item_struct) => item_struct,
+        Err(_) => {
+            return syn::Error::new_spanned(
+                proc_macro2::TokenStream::from(code),
+                "#[cron_obj] can only be applied to a struct",
+            )
+            .into_compile_error()
+            .into()
         }
-        tmp.parse().unwrap()
     };
-
-    // --- start --- building the new_cron_obj function
-    let new_cron_obj: proc_macro2::TokenStream = {
-        let type_name = struct_name.clone().into_token_stream().to_string();
-        let mut function = String::from("fn new_cron_obj(");
-        if !item_struct.fields.is_empty() {
-            let mut tmp = item_struct.fields.iter().map(|x| {
-                let field_name = x.ident.to_token_stream().to_string();
-                let field_type = x.ty.to_token_stream().to_string();
-                format!("{field_name} : {field_type},")
-            });
-
-            for _ in 0..item_struct.fields.len() {
-                function.push_str(&tmp.next().unwrap());
-            }
+    let struct_name = item_struct.ident.clone();
+    let vis = item_struct.vis.clone();
+    let generics = item_struct.generics.clone();
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    // the arguments of new_cron_obj are the fields of the original struct
+    // the fields of a tuple struct are given in order as field_0, field_1, ...
+    let fields: Vec<syn::Field> = item_struct.fields.iter().cloned().collect();
+    let args: Vec<proc_macro2::Ident> = fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            field
+                .ident
+                .clone()
+                .unwrap_or_else(|| format_ident!("field_{index}"))
+        })
+        .collect();
+    let types = fields.iter().map(|field| &field.ty);
+
+    // inject the field carrying the drop signal of method jobs
+    // a tuple struct gets it as its last field, a unit struct becomes a struct with named fields
+    let (drop_signal, constructor) = match &mut item_struct.fields {
+        syn::Fields::Named(named) => {
+            named
+                .named
+                .push(syn::parse_quote! { cf_drop_signal: cronframe::DropSignal });
+            (
+                quote! { cf_drop_signal },
+                quote! { Self { #(#args,)* cf_drop_signal: cronframe::DropSignal::default() } },
+            )
         }
-
-        function.push_str(") -> ");
-        function.push_str(&type_name);
-        function.push_str("{");
-        function.push_str(&type_name);
-        function.push_str("{");
-
-        if !item_struct.fields.is_empty() {
-            let mut tmp = item_struct.fields.iter().map(|x| {
-                let field_name = x.ident.to_token_stream().to_string();
-                format!("{field_name},")
-            });
-
-            for _ in 0..item_struct.fields.len() {
-                function.push_str(&tmp.next().unwrap());
-            }
+        syn::Fields::Unnamed(unnamed) => {
+            let index = syn::Index::from(unnamed.unnamed.len());
+            unnamed
+                .unnamed
+                .push(syn::parse_quote! { cronframe::DropSignal });
+            (
+                quote! { #index },
+                quote! { Self(#(#args,)* cronframe::DropSignal::default()) },
+            )
         }
-        function.push_str("tx: None");
-        function.push_str("}");
-        function.push_str("}");
-        function.parse().unwrap()
-    }; // --- end --- building the new_cron_obj
+        syn::Fields::Unit => {
+            item_struct.fields =
+                syn::Fields::Named(syn::parse_quote! {{ cf_drop_signal: cronframe::DropSignal }});
+            item_struct.semi_token = None;
+            (
+                quote! { cf_drop_signal },
+                quote! { Self { cf_drop_signal: cronframe::DropSignal::default() } },
+            )
+        }
+    };
 
     let new_code = quote! {
-        // the code of the original struct with the addition of the tx field
+        // the code of the original struct with the addition of the drop signal field
         #[derive(Clone)]
-        #struct_edited
+        #item_struct
 
-        // used to keep track of weather function jobs have been gathered
-        static #cf_fn_jobs_flag: std::sync::Mutex<bool> = std::sync::Mutex::new(false);
-        // channels used to manage to drop of function jobs
-        static #cf_fn_jobs_channels: cronframe::Lazy<(cronframe::Sender<cronframe::SchedulerMessage>, cronframe::Receiver<cronframe::SchedulerMessage>)> = cronframe::Lazy::new(|| cronframe::bounded(1));
+        // marks the struct as a cron object for the cron_impl macro
+        impl #impl_generics cronframe::CronObject for #struct_name #ty_generics #where_clause {
+            fn cf_drop_signal(&mut self) -> &mut cronframe::DropSignal {
+                &mut self.#drop_signal
+            }
+        }
 
         // drop for method jobs
-        impl Drop for #struct_name {
-            // this drops method jobs only
+        impl #impl_generics Drop for #struct_name #ty_generics #where_clause {
+            // this drops method jobs only, the instance gathered by cf_gather_mt is the one carrying the signal
             fn drop(&mut self) {
-                if self.tx.is_some(){
-                    let _= self.tx.as_ref().unwrap().send(cronframe::SchedulerMessage::JobDrop);
-                }
+                self.#drop_signal.send();
             }
         }
 
-        // drop for function jobs
-        impl #struct_name {
-            // the new_cron_obj function
-            #new_cron_obj
-
-            // associated funciton of cron objects to drop function jobs
-            fn cf_drop_fn() {
-                if *#cf_fn_jobs_flag.lock().unwrap(){
-                    for func in #function_jobs{
-                        let _= #cf_fn_jobs_channels.0.send(cronframe::SchedulerMessage::JobDrop);
-                    }
-                    *#cf_fn_jobs_flag.lock().unwrap() = false;
-                }
+        impl #impl_generics #struct_name #ty_generics #where_clause {
+            // the new_cron_obj function, the drop signal is set when the cron object is gathered
+            #vis fn new_cron_obj(#(#args: #types),*) -> Self {
+                #constructor
             }
         }
-
-        #[cronframe::distributed_slice]
-        static #method_jobs: [fn(std::sync::Arc<Box<dyn std::any::Any + Send + Sync>>) -> cronframe::JobBuilder<'static>];
-
-        #[cronframe::distributed_slice]
-        static #function_jobs: [fn() -> cronframe::JobBuilder<'static>];
     };
 
     new_code.into()
@@ -169,274 +162,926 @@ pub fn cron_obj(_att: TokenStream, code: TokenStream) -> TokenStream {
 /// Cron Implementation Block Macro
 #[proc_macro_attribute]
 pub fn cron_impl(_att: TokenStream, code: TokenStream) -> TokenStream {
-    let item_impl = syn::parse::<ItemImpl>(code.clone()).unwrap();
-    let r#impl = item_impl.to_token_stream();
-    let impl_items = item_impl.items.clone();
-    let impl_type = item_impl.self_ty.to_token_stream();
-
-    let impl_type_upper = format_ident!(
-        "{}",
-        item_impl
-            .self_ty
-            .to_token_stream()
-            .to_string()
-            .to_uppercase()
-    );
-
-    let method_jobs = format_ident!("CRONFRAME_METHOD_JOBS_{impl_type_upper}");
-    let function_jobs = format_ident!("CRONFRAME_FUNCTION_JOBS_{impl_type_upper}");
-
-    let mut new_code = quote! {
-        #r#impl
+    let item_impl = match syn::parse::<ItemImpl>(code.clone()) {
+        Ok(item_impl) => item_impl,
+        Err(_) => {
+            return syn::Error::new_spanned(
+                proc_macro2::TokenStream::from(code),
+                "#[cron_impl] can only be applied to an impl block",
+            )
+            .into_compile_error()
+            .into()
+        }
     };
+    if let Some((_, path, _)) = &item_impl.trait_ {
+        return syn::Error::new_spanned(
+            path,
+            "#[cron_impl] can only be applied to an inherent impl block, not to a trait implementation",
+        )
+        .into_compile_error()
+        .into();
+    }
 
-    let mut count = 0;
-    for item in impl_items {
-        let item_token = item.to_token_stream();
-        let item_fn_parsed = syn::parse::<ItemFn>(item_token.into());
-        let item_fn_id = item_fn_parsed.clone().unwrap().sig.ident;
-        let helper = format_ident!("cron_helper_{}", item_fn_id);
-        let item_fn_id_upper = format_ident!(
-            "{}",
-            item_fn_id.to_token_stream().to_string().to_uppercase()
-        );
-        let linkme_deserialize = format_ident!("LINKME_{}_{count}", item_fn_id_upper);
+    let impl_type = &item_impl.self_ty;
+    let (impl_generics, _, where_clause) = item_impl.generics.split_for_impl();
 
-        let new_code_tmp = if check_self(&item_fn_parsed) {
-            // method job
-            quote! {
-                #[cronframe::distributed_slice(#method_jobs)]
-                static #linkme_deserialize: fn(_self: std::sync::Arc<Box<dyn std::any::Any + Send + Sync>>)-> cronframe::JobBuilder<'static> = #impl_type::#helper;
-            }
-        } else {
-            // function job
-            quote! {
-                #[cronframe::distributed_slice(#function_jobs)]
-                static #linkme_deserialize: fn()-> cronframe::JobBuilder<'static> = #impl_type::#helper;
-            }
+    // the name of the cron object in the logs, e.g. POLLER<T> or INNER::SAME
+    let type_name = impl_type
+        .to_token_stream()
+        .to_string()
+        .replace(' ', "")
+        .to_uppercase();
+
+    let mut new_code = item_impl.to_token_stream();
+
+    // the helpers of the jobs are listed in the gather functions of the cron object
+    let mut method_jobs = Vec::new();
+    let mut function_jobs = Vec::new();
+    for item in &item_impl.items {
+        // only functions can be jobs, those without a job attribute are left as they are
+        // the error does not stop the expansion so that the errors of the jobs are reported too
+        let syn::ImplItem::Fn(item_fn) = item else {
+            new_code.extend(
+                syn::Error::new_spanned(
+                    item,
+                    "only functions and methods are allowed in a #[cron_impl] block",
+                )
+                .into_compile_error(),
+            );
+            continue;
         };
-
-        new_code.extend(new_code_tmp.into_iter());
-        count += 1;
+        let helper = format_ident!("cron_helper_{}", item_fn.sig.ident);
+        match job_attribute(&item_fn.attrs) {
+            Some(true) => method_jobs.push(helper),
+            Some(false) => function_jobs.push(helper),
+            None => (),
+        }
     }
 
-    let type_name = impl_type.to_string().to_uppercase();
-
-    let cf_fn_jobs_flag = format_ident!("CF_FN_JOBS_FLAG_{}", type_name);
-    let cf_fn_jobs_channels = format_ident!("CF_FN_JOBS_CHANNELS_{}", type_name);
+    // the type must be defined with cron_obj, the error points at the type of the impl block
+    let drop_signal = quote_spanned! { impl_type.span() =>
+        cronframe::CronObject::cf_drop_signal(&mut self)
+    };
 
     let gather_fn = quote! {
-        impl #impl_type{
-            pub fn cf_gather_mt(&mut self, frame: std::sync::Arc<CronFrame>){
-                cronframe::info!("Collecting Method Jobs from {}", #type_name);
-                if !#method_jobs.is_empty(){
-                    let life_channels = cronframe::bounded(1);
-                    self.tx = Some(life_channels.0.clone());
+        impl #impl_generics #impl_type #where_clause {
+            // the state of the function jobs, shared by every instance of the cron object
+            // a generic cron object keeps the state of each instantiation under its TypeId
+            fn cf_function_jobs() -> &'static cronframe::FunctionJobs {
+                static FUNCTION_JOBS: cronframe::FunctionJobs = cronframe::FunctionJobs::new();
+                &FUNCTION_JOBS
+            }
+
+            // the cron object is moved into the returned handle, which is shared with its method jobs
+            // the method jobs are dropped along with the last clone of the handle
+            #[must_use = "the method jobs are dropped along with the returned handle"]
+            pub fn cf_gather_mt(mut self, frame: std::sync::Arc<cronframe::CronFrame>) -> std::sync::Arc<std::sync::Mutex<Self>>
+            where
+                Self: Send + 'static,
+            {
+                let method_jobs: &[fn(std::sync::Arc<Box<dyn std::any::Any + Send + Sync>>) -> cronframe::JobBuilder<'static>] = &[#(Self::#method_jobs),*];
 
-                    for method_job in #method_jobs {
-                        let job_builder = (method_job)(std::sync::Arc::new(Box::new(self.clone())));
-                        let mut cron_job = job_builder.build();
+                cronframe::info!("Collecting Method Jobs from {}", #type_name);
+                let life_channels = cronframe::bounded(method_jobs.len().max(1));
+                let drop_signal = #drop_signal;
+                if !method_jobs.is_empty() {
+                    *drop_signal = cronframe::DropSignal::new(life_channels.0.clone());
+                }
+                let cron_obj = std::sync::Arc::new(std::sync::Mutex::new(self));
+
+                if !method_jobs.is_empty(){
+                    for method_job in method_jobs {
+                        // the jobs only keep a weak reference, so that the handle owns the cron object
+                        let job_builder = (method_job)(std::sync::Arc::new(Box::new(std::sync::Arc::downgrade(&cron_obj))));
+                        let mut cron_job = match job_builder.build() {
+                            Ok(cron_job) => cron_job,
+                            Err(error) => {
+                                cronframe::error!("Method Job Error from {} - {}", #type_name, error);
+                                continue;
+                            }
+                        };
                         cron_job.add_life_channels(life_channels.clone());
                         cronframe::info!("Found Method Job \"{}\" from {}.", cron_job.name(), #type_name);
-                        frame.clone().add_job(cron_job);
+                        if let Err(error) = frame.clone().add_job(cron_job) {
+                            cronframe::error!("Method Job Error from {} - {}", #type_name, error);
+                        }
                     }
                     cronframe::info!("Method Jobs from {} Collected.", #type_name);
                 } else {
                     cronframe::info!("Not Method Jobs from {} has been found.", #type_name);
                 }
+                cron_obj
             }
 
-            pub fn cf_gather_fn(frame: std::sync::Arc<CronFrame>){
+            pub fn cf_gather_fn(frame: std::sync::Arc<cronframe::CronFrame>)
+            where
+                Self: 'static,
+            {
+                let function_jobs: &[fn() -> cronframe::JobBuilder<'static>] = &[#(Self::#function_jobs),*];
+
                 cronframe::info!("Collecting Function Jobs from {}", #type_name);
-                if !#function_jobs.is_empty(){
+                if !function_jobs.is_empty(){
                     // collect jobs from associated functions only if this is the first
                     // instance of this cron object to call the helper_gatherer function
-                    let fn_flag = *#cf_fn_jobs_flag.lock().unwrap();
-
-                    if !fn_flag {
-                        for function_job in #function_jobs {
+                    Self::cf_function_jobs().gather(std::any::TypeId::of::<Self>(), |life_channels| {
+                        for function_job in function_jobs {
                             let job_builder = (function_job)();
-                            let mut cron_job = job_builder.build();
-                            cron_job.add_life_channels(#cf_fn_jobs_channels.clone());
+                            let mut cron_job = match job_builder.build() {
+                                Ok(cron_job) => cron_job,
+                                Err(error) => {
+                                    cronframe::error!("Function Job Error from {} - {}", #type_name, error);
+                                    continue;
+                                }
+                            };
+                            cron_job.add_life_channels(life_channels.clone());
                             cronframe::info!("Found Function Job \"{}\" from {}.", cron_job.name(), #type_name);
-                            frame.clone().add_job(cron_job);
+                            if let Err(error) = frame.clone().add_job(cron_job) {
+                                cronframe::error!("Function Job Error from {} - {}", #type_name, error);
+                            }
                         }
                         cronframe::info!("Function Jobs from {} Collected.", #type_name);
-                        *#cf_fn_jobs_flag.lock().unwrap() = true;
-                    }
+                    });
                 } else {
                     cronframe::info!("Not Function Jobs from {} has been found.", #type_name);
                 }
             }
 
-            pub fn cf_gather(&mut self, frame: std::sync::Arc<CronFrame>){
+            #[must_use = "the method jobs are dropped along with the returned handle"]
+            pub fn cf_gather(self, frame: std::sync::Arc<cronframe::CronFrame>) -> std::sync::Arc<std::sync::Mutex<Self>>
+            where
+                Self: Send + 'static,
+            {
                 Self::cf_gather_fn(frame.clone());
-                self.cf_gather_mt(frame.clone());
+                self.cf_gather_mt(frame.clone())
+            }
+
+            // associated funciton of cron objects to drop function jobs
+            pub fn cf_drop_fn()
+            where
+                Self: 'static,
+            {
+                let function_jobs: &[fn() -> cronframe::JobBuilder<'static>] = &[#(Self::#function_jobs),*];
+                Self::cf_function_jobs().drop_jobs(std::any::TypeId::of::<Self>(), function_jobs.len());
             }
         }
     };
 
-    new_code.extend(gather_fn.into_iter());
+    new_code.extend(gather_fn);
     new_code.into()
 }
 
 /// Function Job definition Macro for a Cron Object
 #[proc_macro_attribute]
 pub fn fn_job(att: TokenStream, code: TokenStream) -> TokenStream {
-    let parsed = syn::parse::<ItemFn>(code.clone());
+    let args = parse_macro_input!(att with Punctuated::<Meta, syn::Token![,]>::parse_terminated);
+    function_job(args, code.clone().into())
+        .unwrap_or_else(|error| job_error(error, code.into(), Some(quote! {})))
+        .into()
+}
 
-    if check_self(&parsed) {
-        // self is present -> compilation error
+// the code of the fn_job macro, misuses are reported as errors spanned on the faulty tokens
+fn function_job(
+    args: Punctuated<Meta, syn::Token![,]>,
+    code: proc_macro2::TokenStream,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let parsed = syn::parse2::<ItemFn>(code.clone()).map_err(|_| {
+        syn::Error::new_spanned(
+            &code,
+            "#[fn_job] can only be applied to an associated function",
+        )
+    })?;
+
+    // a function job has no instance of the cron object to run on
+    if let Some(syn::FnArg::Receiver(receiver)) = parsed.sig.inputs.first() {
+        return Err(syn::Error::new_spanned(
+            receiver,
+            "#[fn_job] cannot be applied to a method, use #[mt_job] for jobs taking self",
+        ));
     }
 
-    // generate code for a function job
-    let args = parse_macro_input!(att with Punctuated::<Meta, syn::Token![,]>::parse_terminated);
-
-    let args = args.into_iter().map(|x| {
-        x.require_name_value()
-            .map(|x| {
-                let arg_name = x.path.to_token_stream().to_string();
-                let arg_val = x.value.to_token_stream().to_string();
-                (arg_name, arg_val.replace("\"", ""))
-            })
-            .unwrap()
-    });
+    let args = job_args(&args)?;
 
-    // should contain ("expr", "* * * * * *")
-    let (arg_1_name, cron_expr) = args.clone().peekable().nth(0).unwrap();
+    // expr = "* * * * * *", every = "90s" or delay = "10m" and an optional timeout = "time in ms"
+    let trigger_arg = trigger_arg(&args, "fn_job")?;
+    let timeout_arg = find_arg(&args, "timeout");
+    check_job_args(trigger_arg, timeout_arg)?;
 
-    // should contain ("timeout", "time in ms")
-    let (arg_2_name, timeout) = args.peekable().nth(1).unwrap();
+    let options = job_options(&args)?;
 
-    if arg_1_name != "expr" && arg_2_name != "timeout" {
-        // wrong argument names -> compilation error
-        return code;
-    }
+    let cron_expr = trigger_expr(trigger_arg)?.unwrap_or_else(|| trigger_arg.value.clone());
+    let timeout = timeout_arg.map_or("0", |arg| arg.value.trim());
 
-    let origin_function = parsed.clone().unwrap().to_token_stream();
-    let ident = parsed.clone().unwrap().sig.ident;
-    let job_name = ident.to_string();
+    let ident = &parsed.sig.ident;
+    let job_name = job_name(&args, ident)?;
     let helper = format_ident!("cron_helper_{}", ident);
 
-    let new_code = quote! {
+    let (job, wrapper) = job_fn(&parsed.sig, quote! { Self:: });
+
+    Ok(quote! {
         // original function
         #[allow(dead_code)]
-        #origin_function
+        #parsed
+
+        #wrapper
 
         fn #helper() -> cronframe::JobBuilder<'static> {
-            cronframe::JobBuilder::function_job(#job_name, Self::#ident, #cron_expr, #timeout)
+            cronframe::JobBuilder::Function {
+                name: #job_name,
+                job: #job,
+                trigger: cronframe::JobTrigger::expr(#cron_expr),
+                timeout: #timeout,
+                options: #options,
+            }
         }
-    };
-    new_code.into()
+    })
 }
 
 /// Method Job definition Macro for a Cron Object
 #[proc_macro_attribute]
 pub fn mt_job(att: TokenStream, code: TokenStream) -> TokenStream {
-    let parsed = syn::parse::<ItemFn>(code.clone());
+    let args = parse_macro_input!(att with Punctuated::<Meta, syn::Token![,]>::parse_terminated);
+    let helper_args = quote! { arg: std::sync::Arc<Box<dyn std::any::Any + Send + Sync>> };
+    method_job(args, code.clone().into())
+        .unwrap_or_else(|error| job_error(error, code.into(), Some(helper_args)))
+        .into()
+}
 
-    if !check_self(&parsed) {
-        // self is missing -> compilation error
+// the code of the mt_job macro, misuses are reported as errors spanned on the faulty tokens
+fn method_job(
+    args: Punctuated<Meta, syn::Token![,]>,
+    code: proc_macro2::TokenStream,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let parsed = syn::parse2::<ItemFn>(code.clone())
+        .map_err(|_| syn::Error::new_spanned(&code, "#[mt_job] can only be applied to a method"))?;
+
+    // a method job runs on the instance of the cron object it was gathered from
+    if !has_self(&parsed.sig) {
+        return Err(syn::Error::new_spanned(
+            &parsed.sig,
+            "#[mt_job] requires a method taking self, use #[fn_job] for associated functions",
+        ));
     }
 
-    // generate code for a function job
-    let args = parse_macro_input!(att with Punctuated::<Meta, syn::Token![,]>::parse_terminated);
+    let args = job_args(&args)?;
 
-    let args = args.into_iter().map(|x| {
-        x.require_name_value()
-            .map(|x| {
-                let arg_name = x.path.to_token_stream().to_string();
-                let arg_val = x.value.to_token_stream().to_string();
-                (arg_name, arg_val.replace("\"", ""))
-            })
-            .unwrap()
-    });
+    // expr = "name of expression field", every = "90s" or delay = "10m"
+    let trigger_arg = trigger_arg(&args, "mt_job")?;
+    let timeout_arg = find_arg(&args, "timeout");
 
-    // should contain ("expr", "name of expression field")
-    let (arg_1_name, cron_expr) = args.clone().peekable().nth(0).unwrap();
+    let options = job_options(&args)?;
 
-    if arg_1_name != "expr" {
-        // wrong argument name -> compilation error
+    // an interval or a delay replaces the expression field, along with an optional timeout
+    let trigger = trigger_expr(trigger_arg)?;
+    match (&trigger, timeout_arg) {
+        (None, Some(timeout_arg)) => {
+            return Err(syn::Error::new(
+                timeout_arg.name_span,
+                "the timeout of a method job is read from its expression field, \
+                `timeout` can only be given along with `every` or `delay`",
+            ))
+        }
+        (Some(_), Some(timeout_arg)) => check_timeout(timeout_arg)?,
+        _ => (),
     }
+    let expr_field = match trigger {
+        Some(_) => None,
+        None => Some(
+            syn::parse_str::<syn::Member>(trigger_arg.value.trim()).map_err(|_| {
+                syn::Error::new(
+                    trigger_arg.span,
+                    format!(
+                        "invalid expr \"{}\", expected the name or the index of a field of the cron object",
+                        trigger_arg.value
+                    ),
+                )
+            })?,
+        ),
+    };
 
     // generate code for a method job
-    let origin_method = parsed.clone().unwrap().to_token_stream();
-    let ident = parsed.clone().unwrap().sig.ident;
-    let job_name = ident.to_string();
-    let block = parsed.clone().unwrap().block;
+    let ident = parsed.sig.ident.clone();
+    let job_name = job_name(&args, &ident)?;
 
     let cronframe_method = format_ident!("cron_method_{}", ident);
     let helper = format_ident!("cron_helper_{}", ident);
-    let expr = format_ident!("expr");
-    let tout = format_ident!("tout");
-
-    // this is to replace the native self with the self from cronframe
-    let block_string = block.clone().into_token_stream().to_string();
-    let mut block_string_edited = block_string.replace("self.", "cronframe_self.");
-    block_string_edited.insert_str(
-        1,
-        "let cron_frame_instance = arg.clone();
-        let cronframe_self = (*cron_frame_instance).downcast_ref::<Self>().unwrap();",
-    );
-
-    let block_edited: proc_macro2::TokenStream = block_string_edited.parse().unwrap();
 
-    //println!("UNEDITED BLOCK:\n{block_string}");
-    //println!("EDITED BLOCK:\n{block_string_edited}");
+    // the job runs on the instance shared with the handle returned by cf_gather_mt
+    // &self and &mut self methods run with the instance locked, self methods on a clone of it
+    let Some(syn::FnArg::Receiver(receiver)) = parsed.sig.inputs.first() else {
+        unreachable!("checked by has_self")
+    };
+    if receiver.colon_token.is_some() {
+        return Err(syn::Error::new_spanned(
+            receiver,
+            "#[mt_job] supports methods taking self, &self or &mut self",
+        ));
+    }
+    let by_value = receiver.reference.is_none();
+
+    // methods can optionally take the context of the run as argument after self
+    // and return a Result whose error is turned into a message
+    let with_ctx = parsed.sig.inputs.len() > 1;
+    let fallible = !matches!(parsed.sig.output, syn::ReturnType::Default);
+    let cron_obj = quote! {
+        (*arg)
+            .downcast_ref::<std::sync::Weak<std::sync::Mutex<Self>>>()
+            .and_then(std::sync::Weak::upgrade)
+    };
+    let lock = quote! { lock().unwrap_or_else(std::sync::PoisonError::into_inner) };
+
+    let (job, job_code) = if parsed.sig.asyncness.is_some() {
+        // async methods run on a clone of the instance taken when the run starts
+        // as the lock of the instance cannot be held by the future
+        if receiver.reference.is_some() && receiver.mutability.is_some() {
+            return Err(syn::Error::new_spanned(
+                receiver,
+                "async method jobs cannot take &mut self, use &self or self",
+            ));
+        }
+        let wrapper = format_ident!("cron_async_{}", ident);
+        let call = if with_ctx {
+            quote! { cron_obj.#ident(&cronframe_ctx).await }
+        } else {
+            quote! { cron_obj.#ident().await }
+        };
+        let result = if fallible {
+            quote! { #call.map_err(|error| error.to_string()) }
+        } else {
+            quote! { #call; Ok::<(), String>(()) }
+        };
+        (
+            quote! { cronframe::MethodFn::Async(Self::#wrapper) },
+            quote! {
+                #[allow(unused_variables)]
+                fn #wrapper(arg: std::sync::Arc<Box<dyn std::any::Any + Send + Sync>>, cronframe_ctx: cronframe::JobContext) -> cronframe::JobFuture
+                where
+                    Self: Send + 'static,
+                {
+                    let cron_obj = #cron_obj.map(|cron_obj| cron_obj.#lock.clone());
+                    Box::pin(async move {
+                        // the cron object has been dropped along with its jobs
+                        let Some(cron_obj) = cron_obj else {
+                            return Ok(());
+                        };
+                        #result
+                    })
+                }
+            },
+        )
+    } else {
+        let (ctx_param, ctx) = if with_ctx {
+            (quote! { , ctx: &cronframe::JobContext }, quote! { ctx })
+        } else {
+            (quote! {}, quote! {})
+        };
+        // the instance is bound to a variable so that the lock is released before the end of the job
+        let receiver = if by_value {
+            quote! { let cron_obj = cron_obj.#lock.clone(); }
+        } else if receiver.mutability.is_some() {
+            quote! { let mut cron_obj = cron_obj.#lock; }
+        } else {
+            quote! { let cron_obj = cron_obj.#lock; }
+        };
+        let (output, call, dropped) = if fallible {
+            (
+                quote! { -> Result<(), String> },
+                quote! { cron_obj.#ident(#ctx).map_err(|error| error.to_string()) },
+                quote! { Ok(()) },
+            )
+        } else {
+            (quote! {}, quote! { cron_obj.#ident(#ctx) }, quote! {})
+        };
+        let job = match (with_ctx, fallible) {
+            (false, false) => quote! { cronframe::MethodFn::Plain(Self::#cronframe_method) },
+            (true, false) => quote! { cronframe::MethodFn::Context(Self::#cronframe_method) },
+            (false, true) => quote! { cronframe::MethodFn::Fallible(Self::#cronframe_method) },
+            (true, true) => {
+                quote! { cronframe::MethodFn::FallibleContext(Self::#cronframe_method) }
+            }
+        };
+        (
+            job,
+            quote! {
+                fn #cronframe_method(arg: std::sync::Arc<Box<dyn std::any::Any + Send + Sync>> #ctx_param) #output
+                where
+                    Self: Send + 'static,
+                {
+                    // the cron object has been dropped along with its jobs
+                    let Some(cron_obj) = #cron_obj else {
+                        return #dropped;
+                    };
+                    #receiver
+                    #call
+                }
+            },
+        )
+    };
 
     let mut new_code = quote! {
-        // original method at the user's disposal
-        #[allow(dead_code)]
-        #origin_method
+        // original method, called by cronframe on the instance of the cron object
+        #parsed
 
         // cronjob method at cronframe's disposal
         // fn cron_method_<name_of_method> ...
-        fn #cronframe_method(arg: std::sync::Arc<Box<dyn std::any::Any + Send + Sync>>) #block_edited
+        #job_code
     };
 
+    // the expression and timeout are read from the field of the cron object named in the expr argument
+    let (cron_expr, timeout) = match (trigger, expr_field) {
+        (Some(trigger), _) => {
+            let timeout = timeout_arg.map_or("0", |arg| arg.value.trim());
+            (
+                quote! { #trigger.to_string() },
+                quote! { #timeout.to_string() },
+            )
+        }
+        (None, expr_field) => (
+            quote! { this_obj.#expr_field.expr() },
+            quote! { format!("{}", this_obj.#expr_field.timeout()) },
+        ),
+    };
     let helper_code = quote! {
         // fn cron_helper_<name_of_method> ...
-        fn #helper(arg: std::sync::Arc<Box<dyn std::any::Any + Send + Sync>>) -> cronframe::JobBuilder<'static> {
-            let instance = arg.clone();
-            let this_obj = (*instance).downcast_ref::<Self>().unwrap();
+        fn #helper(arg: std::sync::Arc<Box<dyn std::any::Any + Send + Sync>>) -> cronframe::JobBuilder<'static>
+        where
+            Self: Send + 'static,
+        {
+            let instance = #cron_obj.unwrap();
+            let this_obj = instance.#lock;
+
+            cronframe::JobBuilder::Method {
+                name: #job_name,
+                job: #job,
+                trigger: cronframe::JobTrigger::from(#cron_expr),
+                timeout: #timeout,
+                instance: arg.clone(),
+                options: #options,
+            }
+        }
+    };
 
-            let #expr = this_obj.cron_expr.expr();
-            let #tout = format!("{}", this_obj.cron_expr.timeout());
-            let instance = arg.clone();
+    new_code.extend(helper_code);
 
-            cronframe::JobBuilder::method_job(#job_name, Self::#cronframe_method, #expr.clone(), #tout, instance)
+    Ok(new_code)
+}
+
+// aid function for the timezone argument, it turns an IANA name into the name of its Tz variant
+// the zone is looked up in chrono-tz, whose variants are named after the zones the same way
+fn tz_variant(name: &str) -> Result<proc_macro2::Ident, String> {
+    let name = name.trim();
+    let timezone = match chrono_tz::Tz::from_str(name) {
+        Ok(timezone) => timezone,
+        Err(_) => {
+            let hint = chrono_tz::TZ_VARIANTS
+                .iter()
+                .find(|timezone| timezone.name().eq_ignore_ascii_case(name))
+                .map(|timezone| format!(", did you mean \"{}\"?", timezone.name()))
+                .unwrap_or_else(|| ", expected an IANA name e.g. \"Europe/Rome\"".to_string());
+            return Err(format!("unknown timezone \"{name}\"{hint}"));
         }
     };
 
-    // replace the placeholder cron_expr with the name of the field
-    let helper_code_edited = helper_code
-        .clone()
-        .into_token_stream()
-        .to_string()
-        .replace("cron_expr", &cron_expr);
-    let block_edited: proc_macro2::TokenStream = helper_code_edited.parse().unwrap();
+    let variant = timezone.name().replace('/', "__").replace('+', "Plus");
+    let variant = match variant.find('-') {
+        Some(pos) if variant[pos + 1..].starts_with(|c: char| c.is_numeric()) => {
+            variant.replace('-', "Minus")
+        }
+        Some(_) => variant.replace('-', ""),
+        None => variant,
+    };
+    syn::parse_str::<proc_macro2::Ident>(&variant).map_err(|error| error.to_string())
+}
 
-    new_code.extend(block_edited.into_iter());
+// aid function for the job macros, the code emitted in place of a job whose definition is wrong
+// the error is followed by the original function and, for the jobs of cron objects, a stub of the
+// helper registered by cron_impl, so that the error is not buried under errors about missing items
+fn job_error(
+    error: syn::Error,
+    code: proc_macro2::TokenStream,
+    helper_args: Option<proc_macro2::TokenStream>,
+) -> proc_macro2::TokenStream {
+    let mut new_code = error.into_compile_error();
+    let Ok(parsed) = syn::parse2::<ItemFn>(code.clone()) else {
+        new_code.extend(code);
+        return new_code;
+    };
 
-    new_code.into()
+    new_code.extend(quote! {
+        #[allow(dead_code)]
+        #parsed
+    });
+    if let Some(helper_args) = helper_args {
+        let helper = format_ident!("cron_helper_{}", parsed.sig.ident);
+        new_code.extend(quote! {
+            #[allow(unused_variables)]
+            fn #helper(#helper_args) -> cronframe::JobBuilder<'static> {
+                unreachable!()
+            }
+        });
+    }
+    new_code
 }
 
-// aid function for fn_job and mt_job
-fn check_self(parsed: &Result<ItemFn, syn::Error>) -> bool {
-    if !parsed.clone().unwrap().sig.inputs.is_empty()
-        && parsed
-            .clone()
-            .unwrap()
-            .sig
-            .inputs
-            .first()
-            .unwrap()
-            .to_token_stream()
-            .to_string()
-            == "self"
-    {
-        true
-    } else {
-        false
+// an argument of the job macros, name = "value", with the spans used by the errors
+struct JobArg {
+    name: String,
+    value: String,
+    name_span: proc_macro2::Span,
+    span: proc_macro2::Span,
+}
+
+// aid function for the job macros, it collects the name = value arguments, which can be given in any order
+// an array of strings, as in tags = ["a", "b"], is turned into a comma separated value
+fn job_args(args: &Punctuated<Meta, syn::Token![,]>) -> syn::Result<Vec<JobArg>> {
+    let mut job_args: Vec<JobArg> = Vec::new();
+
+    for arg in args {
+        let arg = arg.require_name_value()?;
+        let name = arg.path.to_token_stream().to_string();
+        if job_args.iter().any(|job_arg| job_arg.name == name) {
+            return Err(syn::Error::new_spanned(
+                &arg.path,
+                format!("duplicate job argument `{name}`"),
+            ));
+        }
+
+        let value = match &arg.value {
+            syn::Expr::Array(array) => array
+                .elems
+                .iter()
+                .map(arg_value)
+                .collect::<Vec<_>>()
+                .join(","),
+            value => arg_value(value),
+        };
+        job_args.push(JobArg {
+            name,
+            value,
+            name_span: arg.path.span(),
+            span: arg.value.span(),
+        });
+    }
+
+    Ok(job_args)
+}
+
+// aid function for job_args, the content of a string literal or the tokens of any other value
+fn arg_value(value: &syn::Expr) -> String {
+    match value {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(value),
+            ..
+        }) => value.value(),
+        value => value.to_token_stream().to_string(),
+    }
+}
+
+// aid function for the job macros, the argument with the given name
+fn find_arg<'a>(args: &'a [JobArg], name: &str) -> Option<&'a JobArg> {
+    args.iter().find(|arg| arg.name == name)
+}
+
+// aid function for the job macros, the name argument or the name of the function
+// the name is checked the way cronframe checks it when the job is built
+fn job_name(args: &[JobArg], ident: &proc_macro2::Ident) -> syn::Result<String> {
+    let Some(name_arg) = find_arg(args, "name") else {
+        return Ok(ident.to_string());
+    };
+    let name = name_arg.value.trim();
+    if name.is_empty() || name.contains(['/', '\\', '?', '#', '%']) {
+        return Err(syn::Error::new(
+            name_arg.span,
+            format!("invalid job name \"{name}\", it cannot be blank or contain / \\ ? # %"),
+        ));
+    }
+    Ok(name.to_string())
+}
+
+// aid function for the job macros, the argument which sets the trigger of the job
+fn trigger_arg<'a>(args: &'a [JobArg], kind: &str) -> syn::Result<&'a JobArg> {
+    let mut trigger_args = args.iter().filter(|arg| is_trigger_arg(&arg.name));
+    match (trigger_args.next(), trigger_args.next()) {
+        (Some(arg), None) => Ok(arg),
+        (Some(_), Some(arg)) => Err(syn::Error::new(
+            arg.name_span,
+            format!("only one of `expr`, `every` or `delay` can be given in #[{kind}]"),
+        )),
+        (None, _) => Err(syn::Error::new(
+            proc_macro2::Span::call_site(),
+            format!("missing `expr`, `every` or `delay` argument in #[{kind}]"),
+        )),
+    }
+}
+
+// aid function for the job macros, true for the arguments setting the trigger of the job
+fn is_trigger_arg(arg_name: &str) -> bool {
+    matches!(arg_name, "expr" | "every" | "delay")
+}
+
+// aid function for the job macros, it turns an every or delay argument into the expression of the trigger
+// None for the expr argument, whose value is used as it is
+fn trigger_expr(arg: &JobArg) -> syn::Result<Option<String>> {
+    let kind = match arg.name.as_str() {
+        "every" | "delay" => &arg.name,
+        _ => return Ok(None),
+    };
+    match parse_duration_ms(&arg.value) {
+        Some(duration) if duration > 0 => Ok(Some(format!("{kind} {duration}ms"))),
+        _ => Err(syn::Error::new(
+            arg.span,
+            format!(
+                "invalid {kind} \"{}\", expected a positive duration e.g. \"90s\"",
+                arg.value
+            ),
+        )),
+    }
+}
+
+// aid function for cron and fn_job, it checks the expression and the timeout of the job
+// the error is spanned on the faulty argument
+fn check_job_args(expr_arg: &JobArg, timeout_arg: Option<&JobArg>) -> syn::Result<()> {
+    if expr_arg.name == "expr" {
+        check_expr(&expr_arg.value).map_err(|message| syn::Error::new(expr_arg.span, message))?;
+    }
+    match timeout_arg {
+        Some(timeout_arg) => check_timeout(timeout_arg),
+        None => Ok(()),
+    }
+}
+
+// aid function for the job macros, the timeout is a number of ms
+fn check_timeout(timeout_arg: &JobArg) -> syn::Result<()> {
+    match timeout_arg.value.trim().parse::<i64>() {
+        Ok(_) => Ok(()),
+        Err(_) => Err(syn::Error::new(
+            timeout_arg.span,
+            format!(
+                "invalid timeout \"{}\", expected a number of ms e.g. \"0\"",
+                timeout_arg.value
+            ),
+        )),
+    }
+}
+
+// aid function for the job macros, it checks an expression the way cronframe parses it into a Trigger
+// a cron expression, an interval, a delay or an RFC 3339 date time
+fn check_expr(expr: &str) -> Result<(), String> {
+    let expr = expr.trim();
+    for kind in ["every", "delay"] {
+        if let Some(duration) = expr
+            .strip_prefix(kind)
+            .and_then(|rest| rest.strip_prefix(' '))
+        {
+            return match parse_duration_ms(duration) {
+                Some(duration) if duration > 0 => Ok(()),
+                _ => Err(format!(
+                    "invalid {kind} \"{duration}\", expected a positive duration e.g. \"90s\""
+                )),
+            };
+        }
     }
+
+    if chrono::DateTime::parse_from_rfc3339(expr).is_ok() {
+        return Ok(());
+    }
+
+    cron::Schedule::from_str(expr).map(|_| ()).map_err(|error| {
+        let fields = expr.split_whitespace().count();
+        format!(
+            "invalid cron expression \"{expr}\": {error}\n\
+            hint: a cron expression has 6 or 7 fields, found {fields}: \
+            \"sec min hour day_of_month month day_of_week [year]\", e.g. \"0 30 9 * * Mon-Fri\""
+        )
+    })
+}
+
+// aid function for cron and fn_job, it returns the JobFn variant matching the signature of the job
+// along with a wrapper function for jobs returning a Result, which turns the error into a message
+// the path is prepended to the function names, e.g. Self:: for function jobs
+fn job_fn(
+    sig: &syn::Signature,
+    path: proc_macro2::TokenStream,
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    let ident = &sig.ident;
+    let wrapper = format_ident!("cron_result_{}", ident);
+    let with_ctx = !sig.inputs.is_empty();
+    let fallible = !matches!(sig.output, syn::ReturnType::Default);
+
+    // async jobs are wrapped into a function returning a boxed future owning the context
+    if sig.asyncness.is_some() {
+        let wrapper = format_ident!("cron_async_{}", ident);
+        let call = if with_ctx {
+            quote! { #path #ident(&cronframe_ctx).await }
+        } else {
+            quote! { #path #ident().await }
+        };
+        let result = if fallible {
+            quote! { #call.map_err(|error| error.to_string()) }
+        } else {
+            quote! { #call; Ok::<(), String>(()) }
+        };
+
+        return (
+            quote! { cronframe::JobFn::Async(#path #wrapper) },
+            quote! {
+                #[allow(unused_variables)]
+                fn #wrapper(cronframe_ctx: cronframe::JobContext) -> cronframe::JobFuture {
+                    Box::pin(async move { #result })
+                }
+            },
+        );
+    }
+
+    match (with_ctx, fallible) {
+        (false, false) => (quote! { cronframe::JobFn::Plain(#path #ident) }, quote! {}),
+        (true, false) => (
+            quote! { cronframe::JobFn::Context(#path #ident) },
+            quote! {},
+        ),
+        (false, true) => (
+            quote! { cronframe::JobFn::Fallible(#path #wrapper) },
+            quote! {
+                fn #wrapper() -> Result<(), String> {
+                    #path #ident().map_err(|error| error.to_string())
+                }
+            },
+        ),
+        (true, true) => (
+            quote! { cronframe::JobFn::FallibleContext(#path #wrapper) },
+            quote! {
+                fn #wrapper(ctx: &cronframe::JobContext) -> Result<(), String> {
+                    #path #ident(ctx).map_err(|error| error.to_string())
+                }
+            },
+        ),
+    }
+}
+
+// aid function for the job macros, it turns the optional arguments into job options
+// supported arguments: overlap = "Forbid" | "Queue" | "Allow(N)", priority = "N", max_runtime = "<duration>",
+// retries = N, backoff = "fixed:<duration>" | "exp:<duration>", jitter = true | false,
+// catch_up = "Skip" | "FireOnce" | "FireAll(N)", timezone = "<IANA name>",
+// tags = "a, b" | ["a", "b"], suspended = true | false
+// the arguments defining the job itself are skipped: expr, every, delay, timeout and name
+fn job_options(args: &[JobArg]) -> syn::Result<proc_macro2::TokenStream> {
+    let mut options = quote! { cronframe::JobOptions::new() };
+
+    for arg in args {
+        let arg_val = &arg.value;
+        let invalid = |message: String| Err(syn::Error::new(arg.span, message));
+        match arg.name.as_str() {
+            "expr" | "every" | "delay" | "timeout" | "name" => (),
+            "overlap" => {
+                let policy = match arg_val.trim() {
+                    "Forbid" => quote! { cronframe::OverlapPolicy::Forbid },
+                    "Queue" => quote! { cronframe::OverlapPolicy::Queue },
+                    other => match other
+                        .strip_prefix("Allow(")
+                        .and_then(|rest| rest.strip_suffix(')'))
+                        .and_then(|instances| instances.trim().parse::<usize>().ok())
+                    {
+                        Some(instances) => quote! { cronframe::OverlapPolicy::Allow(#instances) },
+                        None => {
+                            let message = format!("unknown overlap policy \"{other}\", expected \"Forbid\", \"Queue\" or \"Allow(N)\"");
+                            return invalid(message);
+                        }
+                    },
+                };
+                options.extend(quote! { .overlap(#policy) });
+            }
+            "priority" => {
+                let Ok(priority) = arg_val.trim().parse::<i32>() else {
+                    let message = format!("invalid priority \"{arg_val}\", expected an integer");
+                    return invalid(message);
+                };
+                options.extend(quote! { .priority(#priority) });
+            }
+            "max_runtime" => {
+                let Some(max_runtime) = parse_duration_ms(arg_val) else {
+                    let message = format!(
+                        "invalid max_runtime \"{arg_val}\", expected a duration e.g. \"30s\""
+                    );
+                    return invalid(message);
+                };
+                options.extend(
+                    quote! { .max_runtime(cronframe::Duration::milliseconds(#max_runtime)) },
+                );
+            }
+            "retries" => {
+                let Ok(retries) = arg_val.trim().parse::<u32>() else {
+                    let message =
+                        format!("invalid retries \"{arg_val}\", expected a non-negative integer");
+                    return invalid(message);
+                };
+                options.extend(quote! { .retries(#retries) });
+            }
+            "backoff" => {
+                let backoff = match arg_val.trim().split_once(':') {
+                    Some(("fixed", delay)) => parse_duration_ms(delay)
+                        .map(|delay| quote! { cronframe::Backoff::Fixed(cronframe::Duration::milliseconds(#delay)) }),
+                    Some(("exp", delay)) => parse_duration_ms(delay)
+                        .map(|delay| quote! { cronframe::Backoff::Exponential(cronframe::Duration::milliseconds(#delay)) }),
+                    _ => None,
+                };
+                let Some(backoff) = backoff else {
+                    let message = format!("invalid backoff \"{arg_val}\", expected \"fixed:<duration>\" or \"exp:<duration>\" e.g. \"exp:1s\"");
+                    return invalid(message);
+                };
+                options.extend(quote! { .backoff(#backoff) });
+            }
+            "jitter" => {
+                let Ok(jitter) = arg_val.trim().parse::<bool>() else {
+                    let message = format!("invalid jitter \"{arg_val}\", expected true or false");
+                    return invalid(message);
+                };
+                options.extend(quote! { .jitter(#jitter) });
+            }
+            "catch_up" => {
+                let policy = match arg_val.trim() {
+                    "Skip" => quote! { cronframe::CatchUpPolicy::Skip },
+                    "FireOnce" => quote! { cronframe::CatchUpPolicy::FireOnce },
+                    other => match other
+                        .strip_prefix("FireAll(")
+                        .and_then(|rest| rest.strip_suffix(')'))
+                        .and_then(|limit| limit.trim().parse::<usize>().ok())
+                    {
+                        Some(limit) => quote! { cronframe::CatchUpPolicy::FireAll(#limit) },
+                        None => {
+                            let message = format!("unknown catch-up policy \"{other}\", expected \"Skip\", \"FireOnce\" or \"FireAll(N)\"");
+                            return invalid(message);
+                        }
+                    },
+                };
+                options.extend(quote! { .catch_up(#policy) });
+            }
+            "timezone" => {
+                let timezone = match tz_variant(arg_val) {
+                    Ok(timezone) => timezone,
+                    Err(message) => return invalid(message),
+                };
+                options.extend(quote! { .timezone(cronframe::Tz::#timezone) });
+            }
+            "tags" => {
+                let tags = arg_val
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|tag| !tag.is_empty());
+                options.extend(quote! { .tags(&[#(#tags),*]) });
+            }
+            "suspended" => {
+                let Ok(suspended) = arg_val.trim().parse::<bool>() else {
+                    let message =
+                        format!("invalid suspended \"{arg_val}\", expected true or false");
+                    return invalid(message);
+                };
+                options.extend(quote! { .suspended(#suspended) });
+            }
+            other => {
+                return Err(syn::Error::new(
+                    arg.name_span,
+                    format!("unknown job argument `{other}`"),
+                ));
+            }
+        }
+    }
+
+    Ok(options)
+}
+
+// aid function for cron_impl, Some(true) for a method job, Some(false) for a function job
+// and None for a function without a job attribute
+fn job_attribute(attrs: &[syn::Attribute]) -> Option<bool> {
+    attrs.iter().find_map(
+        |attr| match attr.path().segments.last()?.ident.to_string().as_str() {
+            "mt_job" => Some(true),
+            "fn_job" => Some(false),
+            _ => None,
+        },
+    )
+}
+
+// aid function for fn_job and mt_job, true when the first argument of the job is self
+fn has_self(sig: &syn::Signature) -> bool {
+    matches!(sig.inputs.first(), Some(syn::FnArg::Receiver(_)))
+}
+
+// aid function for the job macros, it turns a duration like "500ms", "1s", "5m", "2h" or "1d" into ms
+// with the same units as the durations of a Trigger
+fn parse_duration_ms(duration: &str) -> Option<i64> {
+    let duration = duration.trim();
+    let split = duration
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(duration.len());
+    let (value, unit) = duration.split_at(split);
+    let value = value.parse::<i64>().ok()?;
+
+    let factor = match unit.trim() {
+        "ms" => 1,
+        "s" => 1000,
+        "m" => 60 * 1000,
+        "h" => 60 * 60 * 1000,
+        "d" => 24 * 60 * 60 * 1000,
+        _ => return None,
+    };
+    value.checked_mul(factor)
 }
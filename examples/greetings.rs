@@ -18,7 +18,7 @@ impl Greeting {
     }
 
     #[mt_job(expr = "my_expr")]
-    fn specific_greeting_job(self) {
+    fn specific_greeting_job(&self) {
         println!("Hi {}, have a good morning!", self.employee);
     }
 }
@@ -26,12 +26,13 @@ impl Greeting {
 fn main() {
     let cronframe = CronFrame::init().unwrap();
 
-    let mut greeting_john = Greeting::new_cron_obj("John".into(), "0 0 18 * * Mon-Fri * 0".into());
-    let mut greeting_jane =
+    let greeting_john = Greeting::new_cron_obj("John".into(), "0 0 18 * * Mon-Fri * 0".into());
+    let greeting_jane =
         Greeting::new_cron_obj("Jane".into(), CronFrameExpr::from("0 0 16 * * Tue-Thu * 0"));
 
-    greeting_john.cf_gather(cronframe.clone());
-    greeting_jane.cf_gather(cronframe.clone());
+    // the handles keep the method jobs alive
+    let _greeting_john = greeting_john.cf_gather(cronframe.clone());
+    let _greeting_jane = greeting_jane.cf_gather(cronframe.clone());
 
     cronframe.run();
 }
@@ -34,7 +34,9 @@ fn main() {
     let cf = CronFrame::init()
         .unwrap()
         .new_job("hello_job", || println!("hello job"), "* * * * * * *", "0")
-        .new_job("useless_job", useless_job, "0/5 * * * * * *", "0");
+        .unwrap()
+        .new_job("useless_job", useless_job, "0/5 * * * * * *", "0")
+        .unwrap();
 
     wait_seconds(15);
 
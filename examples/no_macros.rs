@@ -1,5 +1,5 @@
 // cronframe framework example
-use cronframe::CronFrame;
+use cronframe::{CronFrame, Duration, Trigger};
 
 //  Cron Expression
 //  * * * * * * *
@@ -21,6 +21,15 @@ fn main() {
     CronFrame::init()
         .unwrap()
         .new_job("hello_job", || println!("hello job"), "* * * * * * *", "0")
+        .unwrap()
         .new_job("useless_job", useless_job, "0/5 * * * * * *", "0")
+        .unwrap()
+        .new_job(
+            "interval_job",
+            || println!("interval job"),
+            Trigger::Interval(Duration::seconds(10)),
+            "0",
+        )
+        .unwrap()
         .run();
 }
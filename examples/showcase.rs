@@ -28,7 +28,8 @@ fn another_test() {
     println!("call from another_test");
 }
 
-#[cron(expr = "0/30 * * * * * *", timeout = "0")]
+// runs due while the previous one is still going are queued
+#[cron(expr = "0/30 * * * * * *", timeout = "0", overlap = "Queue")]
 fn heavy_job() {
     let mut _count: i128 = 0;
 
@@ -67,12 +68,16 @@ impl Users {
     }
 
     #[mt_job(expr = "expr")]
-    fn my_method_job_1(self) {
-        println!("call from my_method_job_1 for expr {}", self.expr.expr());
+    fn my_method_job_1(&self) {
+        println!(
+            "call from my_method_job_1 of {} for expr {}",
+            self.name,
+            self.expr.expr()
+        );
     }
 
     #[mt_job(expr = "expr1")]
-    fn my_method_job_2(self) {
+    fn my_method_job_2(&self) {
         println!("call from my_method_job_2 for expr {}", self.expr1.expr());
     }
 }
@@ -87,18 +92,19 @@ fn main() {
     // inner scope to test the drop of cron_object instances
     {
         println!("PHASE 1");
-        let mut user1 = Users::new_cron_obj("user1".to_string(), expr1.clone(), expr3.clone());
+        let user1 = Users::new_cron_obj("user1".to_string(), expr1.clone(), expr3.clone());
 
         // pass function and method jobs to cronframe
-        user1.cf_gather(cronframe.clone());
+        // method jobs are dropped along with the returned handle
+        let _user1 = user1.cf_gather(cronframe.clone());
         std::thread::sleep(Duration::seconds(10).to_std().unwrap());
 
         println!("PHASE 2");
         {
-            let mut user2 = Users::new_cron_obj("user2".to_string(), expr2, expr3.clone());
+            let user2 = Users::new_cron_obj("user2".to_string(), expr2, expr3.clone());
             // pass function and method jobs to cronframe
             // function jobs will passed again since they already have
-            user2.cf_gather(cronframe.clone());
+            let _user2 = user2.cf_gather(cronframe.clone());
 
             std::thread::sleep(Duration::seconds(10).to_std().unwrap());
         }
@@ -114,9 +120,9 @@ fn main() {
 
     println!("PHASE 4");
 
-    let mut user3 = Users::new_cron_obj("user3".to_string(), expr1, expr3);
+    let user3 = Users::new_cron_obj("user3".to_string(), expr1, expr3);
     // pass function and method jobs to cronframe
-    user3.cf_gather(cronframe.clone());
+    let _user3 = user3.cf_gather(cronframe.clone());
 
     cronframe.keep_alive();
 }
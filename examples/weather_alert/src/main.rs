@@ -60,13 +60,13 @@ impl WeatherAlert {
 }
 
 fn main() {
-    let cronframe = CronFrame::default();
+    let cronframe = CronFrame::init().unwrap();
 
     let alert_schedule = CronFrameExpr::new("0", "0/10", "5-6,14-15", "*", "*", "Mon-Fri", "*", 0);
 
-    let mut venice = WeatherAlert::new_cron_obj("Venice".into(), alert_schedule);
+    let venice = WeatherAlert::new_cron_obj("Venice".into(), alert_schedule);
 
-    venice.cf_gather(cronframe.clone());
+    let _venice = venice.cf_gather(cronframe.clone());
 
     cronframe.run();
 }
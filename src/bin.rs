@@ -1,7 +1,10 @@
+use chrono::Utc;
 use clap::{arg, command};
 use colored::*;
 use cronframe::{
-    utils::{self, ip_and_port},CronFrame,
+    parse_duration,
+    utils::{self, ip_and_port},
+    CronFrame, Trigger,
 };
 use std::{
     fs,
@@ -33,7 +36,7 @@ fn main() {
             clap::Command::new("add")
                 .about("Adds a new cli job to a CronFrame instance.")
                 .args(&[
-                    arg!([EXPR] "The Cron Expression to use for job scheduling."),
+                    arg!([EXPR] "The Cron Expression to use for job scheduling, an RFC 3339 date time or a delay like +10m for a one-shot job."),
                     arg!([TIMEOUT] "The value in ms to use for the timeout."),
                     arg!([JOB] "The path containing the source code of the job."),
                 ])
@@ -66,6 +69,30 @@ fn main() {
                         .action(clap::ArgAction::Set),
                 ),
         )
+        // cronframe run-job NAME
+        .subcommand(
+            clap::Command::new("run-job")
+                .about("Runs a job of a CronFrame instance right away, outside of its schedule.")
+                .args(&[arg!([NAME] "The name of the job to run.")])
+                .arg_required_else_help(true)
+                .arg(
+                    arg!(-p --port <VALUE>)
+                        .required(false)
+                        .action(clap::ArgAction::Set),
+                ),
+        )
+        // cronframe remove NAME
+        .subcommand(
+            clap::Command::new("remove")
+                .about("Removes a cli job from a CronFrame instance.")
+                .args(&[arg!([NAME] "The name of the job to remove.")])
+                .arg_required_else_help(true)
+                .arg(
+                    arg!(-p --port <VALUE>)
+                        .required(false)
+                        .action(clap::ArgAction::Set),
+                ),
+        )
         // cronframe shutdown
         .subcommand(
             clap::Command::new("shutdown")
@@ -93,6 +120,16 @@ fn main() {
             let port_option = sub_matches.get_one::<String>("port");
             scheduler_command(action, port_option);
         }
+        Some(("run-job", sub_matches)) => {
+            let name = sub_matches.get_one::<String>("NAME").unwrap();
+            let port_option = sub_matches.get_one::<String>("port");
+            run_job_command(name, port_option);
+        }
+        Some(("remove", sub_matches)) => {
+            let name = sub_matches.get_one::<String>("NAME").unwrap();
+            let port_option = sub_matches.get_one::<String>("port");
+            remove_command(name, port_option);
+        }
         _ => unreachable!("Exhausted list of subcommands and subcommand_required prevents `None`"),
     }
 }
@@ -109,6 +146,8 @@ fn start_command() {
         return;
     }
 
+    // the instance outlives this command so the child is not waited on
+    #[allow(clippy::zombie_processes)]
     let _build = Command::new("cronframe")
         .args(["run"])
         .stdin(Stdio::null())
@@ -147,12 +186,35 @@ fn run_command() {
         );
         return;
     }
-    let _ = CronFrame::init().unwrap().run();
+    CronFrame::init().unwrap().run();
 }
 
 fn add_command(expr: &str, timeout: &str, job: &str, port_option: Option<&String>) {
     let home_dir = utils::home_dir().replace("\\", "/");
 
+    // a delay is turned into the date time of the one-shot job right away
+    let expr = match one_shot_time(expr) {
+        Ok(Some(fire_time)) => fire_time,
+        Ok(None) => expr.to_string(),
+        Err(error) => {
+            println!("{} {error}", "Error:".red().bold());
+            return;
+        }
+    };
+
+    // the job is not compiled if the instance would refuse it
+    if let Err(error) = expr.parse::<Trigger>() {
+        println!("{} {error}", "Error:".red().bold());
+        return;
+    }
+    if timeout.trim().parse::<i64>().is_err() {
+        println!(
+            "{} invalid timeout '{timeout}', expected a number of ms",
+            "Error:".red().bold()
+        );
+        return;
+    }
+
     let escaped_expr = expr.replace("/", "slh");
 
     let tmp: Vec<_> = if cfg!(target_os = "windows") {
@@ -160,8 +222,8 @@ fn add_command(expr: &str, timeout: &str, job: &str, port_option: Option<&String
     } else {
         job.split("/").collect()
     };
-    let tmp = tmp.iter().filter(|x| !x.is_empty()); // needed if there is a / after the name of the create's folder
-    let job_name = tmp.last().unwrap().replace(".rs", "");
+    let mut tmp = tmp.iter().filter(|x| !x.is_empty()); // needed if there is a / after the name of the create's folder
+    let job_name = tmp.next_back().unwrap().replace(".rs", "");
 
     println!("Compiling {job_name} Job");
 
@@ -175,12 +237,9 @@ fn add_command(expr: &str, timeout: &str, job: &str, port_option: Option<&String
             ])
             .status();
 
-        match compile_command {
-            Err(error) => {
-                println!("{} {}", "Error:".red().bold(), error.to_string());
-                return;
-            }
-            _ => (),
+        if let Err(error) = compile_command {
+            println!("{} {}", "Error:".red().bold(), error);
+            return;
         }
     } else {
         // compile the "crate" job
@@ -194,27 +253,20 @@ fn add_command(expr: &str, timeout: &str, job: &str, port_option: Option<&String
             .current_dir(job)
             .status();
 
-        match compile_command {
-            Err(error) => {
-                println!("{} {}", "Error:".red().bold(), error.to_string());
-                return;
-            }
-            _ => (),
+        if let Err(error) = compile_command {
+            println!("{} {}", "Error:".red().bold(), error);
+            return;
         }
 
         let copy_command = if cfg!(target_os = "windows") {
+            println!("current dir = {home_dir}/.cronframe/cargo_targets/{job_name}/release");
             println!(
-                "current dir = {}",
-                format!("{home_dir}/.cronframe/cargo_targets/{job_name}/release")
-            );
-            println!(
-                "cmd /C copy {} {}",
-                format!("{job_name}.exe"),
+                "cmd /C copy {job_name}.exe {}",
                 format!("{home_dir}/.cronframe/cli_jobs").replace("\\", "/")
             );
 
             Command::new("cmd")
-                .args(&[
+                .args([
                     "/C",
                     "copy",
                     &format!("{job_name}.exe"),
@@ -237,12 +289,9 @@ fn add_command(expr: &str, timeout: &str, job: &str, port_option: Option<&String
                 .status()
         };
 
-        match copy_command {
-            Err(error) => {
-                println!("{} {}", "Error:".red().bold(), error.to_string());
-                return;
-            }
-            _ => (),
+        if let Err(error) = copy_command {
+            println!("{} {}", "Error:".red().bold(), error);
+            return;
         }
     }
 
@@ -253,8 +302,8 @@ fn add_command(expr: &str, timeout: &str, job: &str, port_option: Option<&String
 
     let (ip, mut port) = ip_and_port();
 
-    if port_option.is_some() {
-        port = port_option.unwrap().parse().unwrap();
+    if let Some(port_value) = port_option {
+        port = port_value.parse().unwrap();
     }
 
     if !is_running(&ip, port) {
@@ -268,6 +317,10 @@ fn add_command(expr: &str, timeout: &str, job: &str, port_option: Option<&String
     let req_url = format!("http://{ip}:{port}/add_cli_job/{escaped_expr}/{timeout}/{job_name}");
 
     match reqwest::blocking::get(req_url) {
+        Ok(response) if !response.status().is_success() => {
+            let error = response.text().unwrap_or_default();
+            println!("{} {error}", "Error:".red().bold());
+        }
         Ok(_) => {
             println!("Added Job to CronFrame");
             println!("  Name: {job_name}");
@@ -283,8 +336,8 @@ fn add_command(expr: &str, timeout: &str, job: &str, port_option: Option<&String
 fn scheduler_command(action: &str, port_option: Option<&String>) {
     let (ip, mut port) = ip_and_port();
 
-    if port_option.is_some() {
-        port = port_option.unwrap().parse().unwrap();
+    if let Some(port_value) = port_option {
+        port = port_value.parse().unwrap();
     }
 
     if !is_running(&ip, port) {
@@ -330,6 +383,87 @@ fn scheduler_command(action: &str, port_option: Option<&String>) {
     }
 }
 
+fn run_job_command(name: &str, port_option: Option<&String>) {
+    let (ip, mut port) = ip_and_port();
+
+    if let Some(port_value) = port_option {
+        port = port_value.parse().unwrap();
+    }
+
+    if !is_running(&ip, port) {
+        println!(
+            "{} no instance found at http://{ip}:{port}",
+            "Error:".red().bold()
+        );
+        return;
+    }
+
+    let req_url = format!("http://{ip}:{port}/run_job/{name}");
+
+    match reqwest::blocking::get(req_url) {
+        Ok(response) if response.status() == reqwest::StatusCode::NOT_FOUND => {
+            println!("{} no job named '{name}' found.", "Error:".red().bold());
+        }
+        Ok(response) if response.status() == reqwest::StatusCode::CONFLICT => {
+            println!("{} job '{name}' is suspended.", "Error:".red().bold());
+        }
+        Ok(response) => {
+            println!("Started Job {name}");
+            for run_id in response.text().unwrap_or_default().lines() {
+                println!("  Run Id: {run_id}");
+            }
+        }
+        Err(error) => {
+            println!("{} {error}", "Error:".red().bold());
+        }
+    }
+}
+
+fn remove_command(name: &str, port_option: Option<&String>) {
+    let (ip, mut port) = ip_and_port();
+
+    if let Some(port_value) = port_option {
+        port = port_value.parse().unwrap();
+    }
+
+    if !is_running(&ip, port) {
+        println!(
+            "{} no instance found at http://{ip}:{port}",
+            "Error:".red().bold()
+        );
+        return;
+    }
+
+    let req_url = format!("http://{ip}:{port}/job/{name}");
+
+    match reqwest::blocking::Client::new().delete(req_url).send() {
+        Ok(response) if response.status() == reqwest::StatusCode::NOT_FOUND => {
+            println!("{} no job named '{name}' found.", "Error:".red().bold());
+        }
+        Ok(response) if !response.status().is_success() => {
+            println!(
+                "{} job '{name}' could not be removed, the server answered {}.",
+                "Error:".red().bold(),
+                response.status()
+            );
+        }
+        Ok(_) => {
+            // the binary of the job is no longer needed
+            let home_dir = utils::home_dir();
+            let binary = if cfg!(target_os = "windows") {
+                format!("{name}.exe")
+            } else {
+                name.to_string()
+            };
+            let _ = fs::remove_file(format!("{home_dir}/.cronframe/cli_jobs/{binary}"));
+            println!("Removed Job {name}");
+        }
+        Err(error) => {
+            println!("{} {error}", "Error:".red().bold());
+        }
+    }
+}
+
 fn load_command(file: Option<&String>) {
     let (ip, port) = ip_and_port();
     if !is_running(&ip, port) {
@@ -347,10 +481,16 @@ fn load_command(file: Option<&String>) {
 
     match std::fs::read(file_path) {
         Ok(content) => {
-            for line in content.lines().into_iter() {
+            for line in content.lines() {
                 let line = line.unwrap();
                 let cmpt: Vec<_> = line.split(" ").collect();
 
+                // a one-shot job has a single date time in place of the expr
+                if cmpt.len() == 3 {
+                    add_command(cmpt[0], cmpt[1], cmpt[2], None);
+                    continue;
+                }
+
                 let expr = if cmpt.len() == 9 {
                     // expr made of 7 fields
                     format!(
@@ -372,11 +512,29 @@ fn load_command(file: Option<&String>) {
             }
         }
         Err(err) => {
-            println!("{}", err.to_string());
+            println!("{err}");
         }
     }
 }
 
+// the fire time of a one-shot job given as an RFC 3339 date time or as a delay like +90s, +10m, +2h or +1d
+// None if the expression is not a one-shot one
+fn one_shot_time(expr: &str) -> Result<Option<String>, String> {
+    if let Some(delay) = expr.trim().strip_prefix('+') {
+        let at = parse_duration(delay)
+            .and_then(|delay| Utc::now().checked_add_signed(delay))
+            .ok_or_else(|| {
+                format!("invalid delay '{expr}', expected e.g. +90s, +10m, +2h or +1d")
+            })?;
+        return Ok(Some(Trigger::Once(at).to_string()));
+    }
+
+    match expr.parse::<Trigger>() {
+        Ok(trigger) if trigger.is_once() => Ok(Some(trigger.to_string())),
+        _ => Ok(None),
+    }
+}
+
 fn cronframe_folder() {
     let home_dir = utils::home_dir();
 
@@ -401,8 +559,5 @@ fn cronframe_folder() {
 }
 
 fn is_running(ip: &str, port: u16) -> bool {
-    match reqwest::blocking::get(format!("http://{ip}:{port}")) {
-        Ok(_) => true,
-        Err(_) => false,
-    }
+    reqwest::blocking::get(format!("http://{ip}:{port}")).is_ok()
 }
@@ -0,0 +1,90 @@
+use chrono::{DateTime, Utc};
+use std::{
+    cell::RefCell,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
+
+thread_local! {
+    // token of the run executing on the current worker thread
+    static CURRENT_TOKEN: RefCell<Option<CancellationToken>> = const { RefCell::new(None) };
+}
+
+#[derive(Debug, Default)]
+struct TokenState {
+    cancelled: AtomicBool,
+    deadline: Mutex<Option<DateTime<Utc>>>,
+}
+
+/// Signal telling a running job that it should stop
+///
+/// A job can check the token of its own run with `CancellationToken::current`,
+/// it is cancelled once the run exceeds the `max_runtime` of the job.
+///
+/// ```
+/// use cronframe::CancellationToken;
+///
+/// fn my_job() {
+///     for _ in 0..100 {
+///         if CancellationToken::current().is_some_and(|token| token.is_cancelled()) {
+///             return;
+///         }
+///         // do some work
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    state: Arc<TokenState>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // the token of the run executing on the calling thread, None outside of a job
+    pub fn current() -> Option<CancellationToken> {
+        CURRENT_TOKEN.with(|token| token.borrow().clone())
+    }
+
+    pub fn cancel(&self) {
+        self.state.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.state.cancelled.load(Ordering::SeqCst) || self.timed_out()
+    }
+
+    // true if the token was cancelled explicitly, regardless of the deadline
+    pub(crate) fn cancel_requested(&self) -> bool {
+        self.state.cancelled.load(Ordering::SeqCst)
+    }
+
+    // true if the run went past its deadline
+    pub fn timed_out(&self) -> bool {
+        self.state
+            .deadline
+            .lock()
+            .expect("deadline unwrap error in cancellation token")
+            .is_some_and(|deadline| Utc::now() >= deadline)
+    }
+
+    pub(crate) fn set_deadline(&self, deadline: DateTime<Utc>) {
+        *self
+            .state
+            .deadline
+            .lock()
+            .expect("deadline unwrap error in cancellation token") = Some(deadline);
+    }
+
+    // it makes the token the current one for the duration of the given closure
+    pub(crate) fn scope<R>(&self, f: impl FnOnce() -> R) -> R {
+        CURRENT_TOKEN.with(|token| *token.borrow_mut() = Some(self.clone()));
+        let result = f();
+        CURRENT_TOKEN.with(|token| *token.borrow_mut() = None);
+        result
+    }
+}
@@ -1,9 +1,12 @@
-use crate::{cronjob::CronFilter, utils};
+use crate::{
+    cronjob::{CatchUpPolicy, CronFilter},
+    utils,
+};
+use chrono_tz::Tz;
 use rocket::serde::Deserialize;
 use std::fs;
-use toml;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Default)]
 #[serde(crate = "rocket::serde")]
 pub struct ConfigData {
     pub webserver: ServerConfig,
@@ -11,16 +14,6 @@ pub struct ConfigData {
     pub scheduler: SchedulerConfig,
 }
 
-impl Default for ConfigData {
-    fn default() -> Self {
-        ConfigData {
-            webserver: ServerConfig::default(),
-            logger: LoggerConfig::default(),
-            scheduler: SchedulerConfig::default(),
-        }
-    }
-}
-
 #[derive(Deserialize)]
 #[serde(crate = "rocket::serde")]
 pub struct ConfigDataToml {
@@ -30,35 +23,26 @@ pub struct ConfigDataToml {
 }
 
 impl ConfigDataToml {
+    #[allow(clippy::wrong_self_convention)]
     fn to_config_data(self) -> ConfigData {
         ConfigData {
             webserver: {
-                if self.webserver.is_some() {
+                if let Some(data) = self.webserver {
                     ServerConfig {
-                        port: self
-                            .webserver
-                            .as_ref()
-                            .unwrap()
-                            .port
-                            .unwrap_or_else(|| 8098),
-                        ip: self
-                            .webserver
-                            .unwrap()
-                            .ip
-                            .unwrap_or_else(|| "127.0.0.1".to_string()),
+                        port: data.port.unwrap_or(8098),
+                        ip: data.ip.unwrap_or_else(|| "127.0.0.1".to_string()),
                     }
                 } else {
                     ServerConfig::default()
                 }
             },
             logger: {
-                if self.logger.is_some() {
-                    let data = self.logger.unwrap();
+                if let Some(data) = self.logger {
                     LoggerConfig {
-                        enabled: data.enabled.unwrap_or_else(|| true),
+                        enabled: data.enabled.unwrap_or(true),
                         dir: data.dir.unwrap_or_else(|| "log".to_string()),
-                        file_size: data.file_size.unwrap_or_else(|| 1),
-                        archive_files: data.archive_files.unwrap_or_else(|| 3),
+                        file_size: data.file_size.unwrap_or(1),
+                        archive_files: data.archive_files.unwrap_or(3),
                         latest_file_name: data
                             .latest_file_name
                             .unwrap_or_else(|| "latest".to_string()),
@@ -75,11 +59,30 @@ impl ConfigDataToml {
                 }
             },
             scheduler: {
-                if self.scheduler.is_some() {
-                    let data = self.scheduler.unwrap();
+                if let Some(data) = self.scheduler {
                     SchedulerConfig {
-                        job_filter: data.job_filter.unwrap_or_else(|| CronFilter::None),
-                        grace: data.grace.unwrap_or_else(|| 250),
+                        job_filter: data.job_filter.unwrap_or(CronFilter::None),
+                        grace: data.grace.unwrap_or(250),
+                        workers: data.workers.unwrap_or(16),
+                        async_threads: data.async_threads.unwrap_or(4),
+                        history_size: data.history_size.unwrap_or(32),
+                        store: data.store,
+                        catch_up: match data.catch_up.as_deref().map(str::parse) {
+                            Some(Ok(policy)) => policy,
+                            Some(Err(error)) => {
+                                error!("cronframe.toml - {error}");
+                                CatchUpPolicy::Skip
+                            }
+                            None => CatchUpPolicy::Skip,
+                        },
+                        timezone: match data.timezone.as_deref().map(str::parse::<Tz>) {
+                            Some(Ok(timezone)) => timezone,
+                            Some(Err(error)) => {
+                                error!("cronframe.toml - invalid timezone: {error}");
+                                Tz::UTC
+                            }
+                            None => Tz::UTC,
+                        },
                     }
                 } else {
                     SchedulerConfig::default()
@@ -173,6 +176,12 @@ pub struct LoggerConfigToml {
 pub struct SchedulerConfig {
     pub job_filter: CronFilter,
     pub grace: u32,
+    pub workers: usize,
+    pub async_threads: usize,
+    pub history_size: usize,
+    pub store: Option<String>,
+    pub catch_up: CatchUpPolicy,
+    pub timezone: Tz,
 }
 
 impl Default for SchedulerConfig {
@@ -180,6 +189,12 @@ impl Default for SchedulerConfig {
         SchedulerConfig {
             job_filter: CronFilter::None,
             grace: 250,
+            workers: 16,
+            async_threads: 4,
+            history_size: 32,
+            store: None,
+            catch_up: CatchUpPolicy::Skip,
+            timezone: Tz::UTC,
         }
     }
 }
@@ -189,6 +204,12 @@ impl Default for SchedulerConfig {
 pub struct SchedulerConfigToml {
     pub job_filter: Option<CronFilter>,
     pub grace: Option<u32>,
+    pub workers: Option<usize>,
+    pub async_threads: Option<usize>,
+    pub history_size: Option<usize>,
+    pub store: Option<String>,
+    pub catch_up: Option<String>,
+    pub timezone: Option<String>,
 }
 
 /// This function reads cronframe configuration data from a `cronframe.toml` file
@@ -214,16 +235,23 @@ pub struct SchedulerConfigToml {
 /// [scheduler]
 /// job_filter = "None"
 /// grace = 250 # this is in ms
+/// workers = 16
+/// async_threads = 4
+/// history_size = 32 # runs kept per job
+/// store = "jobs.json" # .json, .toml or .db with the sqlite feature
+/// catch_up = "Skip" # or "FireOnce", "FireAll(N)"
+/// timezone = "UTC" # IANA name of the zone cron expressions are evaluated in
 /// ```
 pub fn read_config() -> ConfigData {
-    let filename = if std::env::var("CRONFRAME_CLI").is_ok() {
+    let cli = std::env::var("CRONFRAME_CLI").is_ok();
+    let filename = if cli {
         let home_dir = utils::home_dir();
         &format!("{home_dir}/.cronframe/cronframe.toml")
     } else {
         "cronframe.toml"
     };
 
-    if let Ok(file_content) = fs::read_to_string(filename) {
+    let mut config = if let Ok(file_content) = fs::read_to_string(filename) {
         if let Ok(data) = toml::from_str::<ConfigDataToml>(&file_content) {
             data.to_config_data()
         } else {
@@ -233,5 +261,17 @@ pub fn read_config() -> ConfigData {
     } else {
         info!("cronframe.toml - file not found");
         ConfigData::default()
+    };
+
+    // the cli instance always keeps its jobs in a store inside the .cronframe directory
+    if cli {
+        let home_dir = utils::home_dir();
+        config.scheduler.store = Some(match config.scheduler.store {
+            Some(store) if std::path::Path::new(&store).is_absolute() => store,
+            Some(store) => format!("{home_dir}/.cronframe/{store}"),
+            None => format!("{home_dir}/.cronframe/jobs.json"),
+        });
     }
+
+    config
 }
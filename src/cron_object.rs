@@ -0,0 +1,116 @@
+use crate::SchedulerMessage;
+use crossbeam_channel::{Receiver, Sender};
+use std::{any::TypeId, collections::BTreeMap, sync::Mutex};
+
+/// Marker trait for the types defined with the `cron_obj` macro
+///
+/// It is implemented by the `cron_obj` macro and required by the `cron_impl` macro,
+/// so that an implementation block of a type missing `#[cron_obj]` fails to compile.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is not a cron object",
+    label = "#[cron_impl] requires a type defined with #[cron_obj]",
+    note = "add #[cron_obj] to the definition of `{Self}`"
+)]
+pub trait CronObject {
+    #[doc(hidden)]
+    fn cf_drop_signal(&mut self) -> &mut DropSignal;
+}
+
+/// Field injected by the `cron_obj` macro, it drops the method jobs of a cron object along with it
+///
+/// A clone of the cron object does not carry the signal, only the gathered instance does.
+#[doc(hidden)]
+#[derive(Debug, Default)]
+pub struct DropSignal(Option<Sender<SchedulerMessage>>);
+
+impl DropSignal {
+    // the channel has room for a message per method job of the cron object
+    pub fn new(tx: Sender<SchedulerMessage>) -> Self {
+        DropSignal(Some(tx))
+    }
+
+    // it must not block as the scheduler might be stopped with a drop already pending
+    pub fn send(&self) {
+        if let Some(tx) = &self.0 {
+            for _ in 0..tx.capacity().unwrap_or(1) {
+                let _ = tx.try_send(SchedulerMessage::JobDrop);
+            }
+        }
+    }
+}
+
+impl Clone for DropSignal {
+    fn clone(&self) -> Self {
+        DropSignal(None)
+    }
+}
+
+/// State of the function jobs of a cron object, kept by the `cron_impl` macro in a static
+///
+/// The static of a generic cron object is shared by all its instantiations,
+/// so the state is kept per type, each instantiation gathering and dropping its own function jobs.
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct FunctionJobs {
+    states: Mutex<BTreeMap<TypeId, FunctionJobsState>>,
+}
+
+#[derive(Debug)]
+struct FunctionJobsState {
+    // whether the function jobs have been gathered
+    gathered: bool,
+    // channels used to drop the function jobs
+    channels: (Sender<SchedulerMessage>, Receiver<SchedulerMessage>),
+}
+
+impl FunctionJobs {
+    pub const fn new() -> Self {
+        FunctionJobs {
+            states: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    // it gathers the function jobs of the type unless they are already gathered
+    // the gathering gets the channels used to drop the jobs
+    pub fn gather(
+        &self,
+        type_id: TypeId,
+        gather: impl FnOnce(&(Sender<SchedulerMessage>, Receiver<SchedulerMessage>)),
+    ) {
+        let mut states = self
+            .states
+            .lock()
+            .expect("function jobs unwrap error in gather");
+        let state = states.entry(type_id).or_insert_with(|| FunctionJobsState {
+            gathered: false,
+            channels: crossbeam_channel::bounded(1),
+        });
+
+        if !state.gathered {
+            gather(&state.channels);
+            state.gathered = true;
+        }
+    }
+
+    // it drops the given number of function jobs of the type, if they have been gathered
+    pub fn drop_jobs(&self, type_id: TypeId, count: usize) {
+        let mut states = self
+            .states
+            .lock()
+            .expect("function jobs unwrap error in drop_jobs");
+        if let Some(state) = states.get_mut(&type_id) {
+            if state.gathered {
+                for _ in 0..count {
+                    let _ = state.channels.0.send(SchedulerMessage::JobDrop);
+                }
+                state.gathered = false;
+            }
+        }
+    }
+}
+
+impl Default for FunctionJobs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
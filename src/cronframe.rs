@@ -1,12 +1,24 @@
 use crate::{
-    config::{read_config, ConfigData}, cronjob::{CronFilter, CronJob}, job_builder::JobBuilder, logger, web_server
+    config::{read_config, ConfigData},
+    cronjob::{CronFilter, CronJob, JobFn, MisfirePolicy, OverlapPolicy},
+    history::{RunOrigin, RunOutcome, RunRecord},
+    job_builder::{self, CronFrameError, JobBuilder, JobOptions, JobTrigger},
+    job_store::{self, JobStore, StoreWrite, StoreWriter, StoredJob, StoredRun},
+    logger,
+    trigger::Trigger,
+    web_server,
+    worker_pool::WorkerPool,
+};
+use chrono::{DateTime, Duration, Utc};
+use crossbeam_channel::{Receiver, Select, Sender, TryRecvError};
+use rocket::{
+    tokio::runtime::{Builder, Handle, Runtime},
+    Shutdown,
 };
-use chrono::Duration;
-use crossbeam_channel::{Receiver, Sender};
-use rocket::Shutdown;
 use std::{
-    collections::HashMap,
-    sync::{Arc, Mutex},
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    sync::{Arc, Mutex, OnceLock},
     thread::JoinHandle,
 };
 use uuid::Uuid;
@@ -20,20 +32,159 @@ pub enum CFError {
 
 #[derive(Debug)]
 pub enum SchedulerMessage {
-    JobComplete,
+    JobComplete {
+        run_id: Uuid,
+        start_time: DateTime<Utc>,
+    },
     JobDrop,
-    JobAbort,
+    JobAbort {
+        run_id: Uuid,
+        start_time: DateTime<Utc>,
+    },
+    JobTimedOut {
+        run_id: Uuid,
+        start_time: DateTime<Utc>,
+    },
+    // the max runtime of a run is over while the job is still executing on its worker
+    JobOverrun {
+        run_id: Uuid,
+        start_time: DateTime<Utc>,
+    },
+    // the worker of an overrun run is done with the job
+    JobReturned {
+        run_id: Uuid,
+    },
+    // the run was cancelled before a worker picked it up
+    JobCancelled {
+        run_id: Uuid,
+    },
+    JobFailed {
+        run_id: Uuid,
+        start_time: DateTime<Utc>,
+        error: String,
+    },
+    JobReschedule,
+    SchedulerWake,
+}
+
+// runtime shared by the async jobs of every instance, it lives for the whole process
+// since a tokio runtime cannot be dropped from within an async context
+static ASYNC_RUNTIME: OnceLock<Runtime> = OnceLock::new();
+
+fn async_runtime(threads: usize) -> Handle {
+    ASYNC_RUNTIME
+        .get_or_init(|| {
+            Builder::new_multi_thread()
+                .worker_threads(threads.max(1))
+                .thread_name("cronframe-async")
+                .enable_all()
+                .build()
+                .expect("async runtime build error")
+        })
+        .handle()
+        .clone()
+}
+
+// messages sent to the scheduler along with the id of the job they refer to
+type SchedulerChannels = (
+    Sender<(Uuid, SchedulerMessage)>,
+    Receiver<(Uuid, SchedulerMessage)>,
+);
+
+// queue of upcoming fire times used by the scheduler, the earliest one is on top
+// an entry is stale when it no longer matches the fire time recorded for its job
+#[derive(Default)]
+struct ScheduleQueue {
+    heap: BinaryHeap<Reverse<(DateTime<Utc>, Uuid)>>,
+    next_fire: HashMap<Uuid, DateTime<Utc>>,
+    // retries of failed runs along with the id of their job
+    retries: BinaryHeap<Reverse<(DateTime<Utc>, Uuid, Uuid)>>,
+}
+
+impl ScheduleQueue {
+    fn push(&mut self, job_id: Uuid, fire_time: DateTime<Utc>) {
+        self.next_fire.insert(job_id, fire_time);
+        self.heap.push(Reverse((fire_time, job_id)));
+    }
+
+    fn remove(&mut self, job_id: &Uuid) {
+        self.next_fire.remove(job_id);
+    }
+
+    fn contains(&self, job_id: &Uuid) -> bool {
+        self.next_fire.contains_key(job_id)
+    }
+
+    fn push_retry(&mut self, job_id: Uuid, run_id: Uuid, retry_at: DateTime<Utc>) {
+        self.retries.push(Reverse((retry_at, job_id, run_id)));
+    }
+
+    // time left until the earliest fire time or retry, None if there is nothing to schedule
+    fn until_next(&mut self) -> Option<std::time::Duration> {
+        self.discard_stale();
+        let next_fire = self.heap.peek().map(|Reverse((fire_time, _))| *fire_time);
+        let next_retry = self
+            .retries
+            .peek()
+            .map(|Reverse((retry_at, _, _))| *retry_at);
+
+        next_fire
+            .into_iter()
+            .chain(next_retry)
+            .min()
+            .map(|next| (next - Utc::now()).to_std().unwrap_or_default())
+    }
+
+    fn pop_due_retry(&mut self) -> Option<(Uuid, Uuid)> {
+        match self.retries.peek() {
+            Some(&Reverse((retry_at, job_id, run_id))) if retry_at <= Utc::now() => {
+                self.retries.pop();
+                Some((job_id, run_id))
+            }
+            _ => None,
+        }
+    }
+
+    fn pop_due(&mut self) -> Option<(Uuid, DateTime<Utc>)> {
+        self.discard_stale();
+        match self.heap.peek() {
+            Some(&Reverse((fire_time, job_id))) if fire_time <= Utc::now() => {
+                self.heap.pop();
+                self.next_fire.remove(&job_id);
+                Some((job_id, fire_time))
+            }
+            _ => None,
+        }
+    }
+
+    fn discard_stale(&mut self) {
+        while let Some(Reverse((fire_time, job_id))) = self.heap.peek() {
+            if self.next_fire.get(job_id) == Some(fire_time) {
+                break;
+            }
+            self.heap.pop();
+        }
+    }
 }
 
 pub struct CronFrame {
     job_pool: Mutex<HashMap<Uuid, CronJob>>,
-    job_handles: Mutex<HashMap<Uuid, JoinHandle<()>>>,
+    // executes the runs of the due jobs
+    worker_pool: WorkerPool,
     _logger: Option<log4rs::Handle>,
     rocket_channels: (Sender<Shutdown>, Receiver<Shutdown>),
+    scheduler_channels: SchedulerChannels,
+    scheduler_handle: Mutex<Option<JoinHandle<()>>>,
     server_handle: Mutex<Option<Shutdown>>,
     pub quit: Mutex<bool>,
     pub running: Mutex<bool>,
     config: ConfigData,
+    // keeps the state of the jobs across restarts
+    store: Option<StoreWriter>,
+    // stored jobs still to be matched with the jobs added after init
+    stored_jobs: Mutex<Vec<StoredJob>>,
+    // run history of the one-shot jobs that have fired
+    archive: Mutex<HashMap<Uuid, Vec<RunRecord>>>,
 }
 
 impl CronFrame {
@@ -50,6 +201,29 @@ impl CronFrame {
     }
 
     pub fn with_config(config: ConfigData) -> Result<Arc<CronFrame>, CFError> {
+        let store = match config.scheduler.store.as_deref().map(job_store::open_store) {
+            Some(Ok(store)) => Some(store),
+            Some(Err(error)) => {
+                error!("Job store error: {error}");
+                None
+            }
+            None => None,
+        };
+        Self::start(config, store)
+    }
+
+    /// It initialises the framework with a job store other than the one in the configuration
+    pub fn with_store(
+        config: ConfigData,
+        store: impl JobStore + 'static,
+    ) -> Result<Arc<CronFrame>, CFError> {
+        Self::start(config, Some(Box::new(store)))
+    }
+
+    fn start(
+        config: ConfigData,
+        store: Option<Box<dyn JobStore>>,
+    ) -> Result<Arc<CronFrame>, CFError> {
         println!("Starting CronFrame...");
 
         let logger = if config.logger.enabled {
@@ -58,23 +232,46 @@ impl CronFrame {
             None
         };
 
+        let scheduler_channels: SchedulerChannels = crossbeam_channel::unbounded();
+        let worker_pool = WorkerPool::new(
+            config.scheduler.workers,
+            scheduler_channels.0.clone(),
+            async_runtime(config.scheduler.async_threads),
+        );
+
+        // the stored jobs are loaded before the store is handed over to its writer
+        let stored_jobs = store.as_ref().map(|store| store.load());
+
         let frame = CronFrame {
             job_pool: Mutex::new(HashMap::new()),
-            job_handles: Mutex::new(HashMap::new()),
+            worker_pool,
             _logger: logger,
             rocket_channels: crossbeam_channel::bounded(1),
+            scheduler_channels,
+            scheduler_handle: Mutex::new(None),
             server_handle: Mutex::new(None),
             quit: Mutex::new(false),
             running: Mutex::new(false),
             config,
+            store: store.map(StoreWriter::spawn),
+            stored_jobs: Mutex::new(Vec::new()),
+            archive: Mutex::new(HashMap::new()),
         };
 
         info!("CronFrame Init Start");
         info!("Graceful Period {} ms", frame.config.scheduler.grace);
+        info!("Worker Pool Size {}", frame.worker_pool.size());
         info!("Colleting Global Jobs");
 
         for job_builder in inventory::iter::<JobBuilder> {
-            let cron_job = job_builder.clone().build();
+            let mut cron_job = match job_builder.clone().build() {
+                Ok(cron_job) => cron_job,
+                Err(error) => {
+                    error!("Global Job Error - {error}");
+                    continue;
+                }
+            };
+            cron_job.set_default_timezone(frame.config.scheduler.timezone);
             info!("Found Global Job \"{}\"", cron_job.name());
             frame
                 .job_pool
@@ -84,6 +281,12 @@ impl CronFrame {
         }
 
         info!("Global Jobs Collected");
+
+        match stored_jobs {
+            Some(Ok(stored_jobs)) => frame.restore_jobs(stored_jobs),
+            Some(Err(error)) => error!("Job store load error: {error}"),
+            None => {}
+        }
         info!("CronFrame Init Complete");
         info!("CronFrame Server Init");
 
@@ -91,7 +294,7 @@ impl CronFrame {
         let server_frame = frame.clone();
         let running = Mutex::new(false);
 
-        // rocket webserver 
+        // rocket webserver
         std::thread::spawn(move || web_server::web_server(server_frame));
 
         *frame
@@ -125,13 +328,155 @@ impl CronFrame {
         Ok(frame)
     }
 
-    /// Used in the cf_gather_mt and cf_gather_fn
-    pub fn add_job(self: &Arc<CronFrame>, job: CronJob) -> Arc<CronFrame> {
+    // it applies the stored state to the global jobs and recreates the stored CLI jobs
+    // the state of the jobs added later is applied by add_job
+    fn restore_jobs(&self, stored_jobs: Vec<StoredJob>) {
+        let history_size = self.config.scheduler.history_size;
+        let mut cron_jobs = self
+            .job_pool
+            .lock()
+            .expect("cron jobs unwrap error in restore_jobs");
+        let mut pending = Vec::new();
+        let mut restored = Vec::new();
+
+        for stored_job in stored_jobs {
+            if let Some(mut cron_job) = stored_job.to_cli_job(history_size) {
+                cron_job.set_default_timezone(self.config.scheduler.timezone);
+                info!("Restored CLI Job \"{}\"", cron_job.name());
+                cron_jobs.insert(cron_job.id(), cron_job);
+                continue;
+            }
+
+            // the stored job goes to the first job it matches which has not been restored yet
+            // the job takes the stored id, so it is moved to its new key in the job pool
+            let job_id = cron_jobs
+                .values()
+                .find(|cron_job| stored_job.matches(cron_job) && !restored.contains(&cron_job.id()))
+                .map(|cron_job| cron_job.id());
+
+            match job_id.and_then(|job_id| cron_jobs.remove(&job_id)) {
+                Some(mut cron_job) => {
+                    stored_job.apply(&mut cron_job, history_size);
+                    info!("Restored Job State \"{}\"", stored_job.name);
+                    restored.push(cron_job.id());
+                    cron_jobs.insert(cron_job.id(), cron_job);
+                }
+                None => pending.push(stored_job),
+            }
+        }
+
+        for cron_job in cron_jobs.values() {
+            self.store_write(|| StoreWrite::Save(StoredJob::from_job(cron_job)));
+        }
+        *self
+            .stored_jobs
+            .lock()
+            .expect("stored jobs unwrap error in restore_jobs") = pending;
+    }
+
+    // it hands a write over to the job store writer, if there is a store
+    fn store_write(&self, write: impl FnOnce() -> StoreWrite) {
+        if let Some(store) = &self.store {
+            store.send(write());
+        }
+    }
+
+    // it saves the state of the job in the job store, its run history is left as it is
+    pub(crate) fn persist(&self, cron_job: &CronJob) {
+        self.store_write(|| StoreWrite::SaveState(StoredJob::state_of(cron_job)));
+    }
+
+    // it adds a run to the history of the job in the job store
+    fn persist_run(&self, cron_job: &CronJob, record: &RunRecord) {
+        self.store_write(|| StoreWrite::AppendRun {
+            job_id: cron_job.id().to_string(),
+            name: cron_job.name(),
+            run: StoredRun::from(record),
+            history_size: self.config.scheduler.history_size,
+        });
+    }
+
+    // it removes the job from the job store
+    fn unpersist(&self, cron_job: &CronJob) {
+        self.store_write(|| StoreWrite::Remove {
+            job_id: cron_job.id().to_string(),
+            name: cron_job.name(),
+        });
+    }
+
+    /// It adds a job to the job pool, used in the cf_gather_mt and cf_gather_fn
+    ///
+    /// An error is returned if the name of the job is not valid.
+    pub fn add_job(
+        self: &Arc<CronFrame>,
+        mut job: CronJob,
+    ) -> Result<Arc<CronFrame>, CronFrameError> {
+        job_builder::validate_name(&job.name())?;
+        job.set_default_timezone(self.config.scheduler.timezone);
+
+        // the state of the job saved before a restart, it is applied only once
+        // the job takes the id it had before the restart
+        let stored_job = {
+            let mut stored_jobs = self
+                .stored_jobs
+                .lock()
+                .expect("stored jobs unwrap error in add_job");
+            stored_jobs
+                .iter()
+                .position(|stored_job| stored_job.matches(&job))
+                .map(|index| stored_jobs.swap_remove(index))
+        };
+        if let Some(stored_job) = stored_job {
+            stored_job.apply(&mut job, self.config.scheduler.history_size);
+            info!("Restored Job State \"{}\"", job.name());
+        }
+        self.store_write(|| StoreWrite::Save(StoredJob::from_job(&job)));
+
+        let job_id = job.id();
         self.job_pool
             .lock()
             .expect("add_job unwrap error on lock")
-            .insert(job.id(), job);
-        self.clone()
+            .insert(job_id, job);
+        self.reschedule(job_id);
+        Ok(self.clone())
+    }
+
+    /// It removes a job from the framework and from the job store, returning it
+    ///
+    /// The runs of the job that are going have their cancellation token tripped.
+    /// None if there is no such job.
+    pub fn remove_job(&self, job_id: Uuid) -> Option<CronJob> {
+        let cron_job = self
+            .job_pool
+            .lock()
+            .expect("cron jobs unwrap error in remove_job")
+            .remove(&job_id)?;
+
+        cron_job.cancel_runs();
+        info!("job name@{} - uuid#{} - Removed", cron_job.name(), job_id);
+
+        self.unpersist(&cron_job);
+
+        // the scheduler drops the fire time of a job that is no longer in the pool
+        self.reschedule(job_id);
+        Some(cron_job)
+    }
+
+    /// It removes every job with the given name, see `remove_job`
+    pub fn remove_jobs_by_name(&self, name: &str) -> Vec<CronJob> {
+        let job_ids: Vec<_> = self
+            .job_pool
+            .lock()
+            .expect("cron jobs unwrap error in remove_jobs_by_name")
+            .iter()
+            .filter(|(_, cron_job)| cron_job.name() == name)
+            .map(|(job_id, _)| *job_id)
+            .collect();
+
+        job_ids
+            .into_iter()
+            .filter_map(|job_id| self.remove_job(job_id))
+            .collect()
     }
 
     pub fn job_filter(self: &Arc<CronFrame>) -> CronFilter {
@@ -139,23 +484,63 @@ impl CronFrame {
     }
 
     // It crates a new job classified as a global job and adds it to the job pool
-    pub fn new_job(
+    // the trigger is either a typed Trigger or any trigger expression such as "every 90s" or "delay 10m"
+    pub fn new_job<'a>(
+        self: Arc<CronFrame>,
+        name: &'a str,
+        job: fn(),
+        trigger: impl Into<JobTrigger<'a>>,
+        timeout: &'a str,
+    ) -> Result<Arc<CronFrame>, CronFrameError> {
+        let builder = JobBuilder::Global {
+            name,
+            job: JobFn::Plain(job),
+            trigger: trigger.into(),
+            timeout,
+            options: JobOptions::new(),
+        };
+        self.add_job(builder.build()?)
+    }
+
+    /// It adds a global job that runs once at the given time and then leaves the job pool
+    ///
+    /// ```no_run
+    /// use cronframe::{CronFrame, Duration};
+    ///
+    /// fn my_job() {}
+    ///
+    /// let cronframe = CronFrame::init().unwrap();
+    /// let at = chrono::Utc::now() + Duration::minutes(10);
+    /// cronframe.once_job("my_job", my_job, at, "0").unwrap().run();
+    /// ```
+    pub fn once_job(
         self: Arc<CronFrame>,
         name: &str,
         job: fn(),
-        cron_expr: &str,
+        at: DateTime<Utc>,
         timeout: &str,
-    ) -> Arc<CronFrame> {
-        self.add_job(JobBuilder::global_job(name, job, cron_expr, timeout).build())
+    ) -> Result<Arc<CronFrame>, CronFrameError> {
+        let builder = JobBuilder::global_job_with_trigger(name, job, Trigger::Once(at), timeout);
+        self.add_job(builder.build()?)
     }
 
-    pub fn start_scheduler<'a>(self: &Arc<Self>) -> Arc<Self> {
+    pub fn start_scheduler(self: &Arc<Self>) -> Arc<Self> {
         let cronframe = self.clone();
 
         if *self.running.lock().unwrap() {
             return cronframe;
         }
 
+        // a previously stopped scheduler thread must be gone before a new one takes over the channel
+        if let Some(handle) = self
+            .scheduler_handle
+            .lock()
+            .expect("scheduler handle unwrap error in start_scheduler method")
+            .take()
+        {
+            let _ = handle.join();
+        }
+
         let cronframe_return = cronframe.clone();
 
         *cronframe
@@ -168,149 +553,614 @@ impl CronFrame {
             .expect("quit unwrap error in start_scheduler method") = false;
 
         // closure containg the actual scheduler code
-        let scheduler = move || loop {
-            // sleep some otherwise the cpu consumption goes to the moon
-            std::thread::sleep(Duration::milliseconds(500).to_std().unwrap());
+        // the scheduler sleeps until the earliest due job or until it receives a message
+        let scheduler = move || {
+            let mut queue = ScheduleQueue::default();
+            let mut life_groups: Vec<(Receiver<SchedulerMessage>, Vec<Uuid>)> = Vec::new();
 
-            if *cronframe
-                .quit
-                .lock()
-                .expect("quit unwrap error in scheduler")
-            {
-                break;
-            }
-
-            if !*cronframe
-                .running
+            // jobs resume from their last fire time so that runs missed while
+            // the scheduler was stopped are handled as misfires
+            for cron_job in cronframe
+                .job_pool
                 .lock()
-                .expect("running unwrap error in scheduler")
+                .expect("cron jobs unwrap error in scheduler")
+                .values_mut()
             {
-                break;
+                let after = cron_job.last_fire().unwrap_or_else(Utc::now);
+                cronframe.enqueue_job(cron_job, after, &mut queue, &mut life_groups);
             }
 
-            let mut cron_jobs = cronframe
-                .job_pool
-                .lock()
-                .expect("cron jobs unwrap error in scheduler");
+            loop {
+                let ready = {
+                    let mut select = Select::new();
+                    select.recv(&cronframe.scheduler_channels.1);
+                    for (life_rx, _) in &life_groups {
+                        select.recv(life_rx);
+                    }
 
-            let mut jobs_to_drop: Vec<Uuid> = Vec::new();
+                    match queue.until_next() {
+                        Some(timeout) => select.ready_timeout(timeout).ok(),
+                        None => Some(select.ready()),
+                    }
+                };
 
-            for (job_id, cron_job) in &mut (*cron_jobs).iter_mut() {
-                let filter = cronframe.config.scheduler.job_filter;
+                if *cronframe
+                    .quit
+                    .lock()
+                    .expect("quit unwrap error in scheduler")
+                {
+                    break;
+                }
 
-                // handle the job only if filter and job type match
-                // No filter -> all job types are to be executed
-                if filter != CronFilter::None {
-                    if cron_job.type_filter() != filter {
-                        continue;
-                    }
+                if !*cronframe
+                    .running
+                    .lock()
+                    .expect("running unwrap error in scheduler")
+                {
+                    break;
                 }
 
-                // if cron_obj instance related to the job is dropped delete the job
-                let to_be_dropped = if let Some((_, life_rx)) = cron_job.life_channels() {
-                    match life_rx.try_recv() {
-                        Ok(message) => match message {
-                            SchedulerMessage::JobDrop => {
-                                info!("job name@{} - uuid#{} - Dropped", cron_job.name(), job_id);
-                                jobs_to_drop.push(*job_id);
-                                true
+                match ready {
+                    // messages about job runs and changes to the job pool
+                    Some(0) => {
+                        while let Ok((job_id, message)) = cronframe.scheduler_channels.1.try_recv()
+                        {
+                            cronframe.handle_message(job_id, message, &mut queue, &mut life_groups);
+                            cronframe.settle_job(job_id, &mut queue);
+                        }
+                    }
+                    // if cron_obj instance related to the job is dropped delete the job
+                    Some(index) => {
+                        let (life_rx, job_ids) = &mut life_groups[index - 1];
+                        match life_rx.try_recv() {
+                            Ok(SchedulerMessage::JobDrop) => {
+                                if let Some(job_id) = job_ids.pop() {
+                                    if let Some(cron_job) = cronframe
+                                        .job_pool
+                                        .lock()
+                                        .expect("cron jobs unwrap error in scheduler")
+                                        .remove(&job_id)
+                                    {
+                                        info!(
+                                            "job name@{} - uuid#{} - Dropped",
+                                            cron_job.name(),
+                                            job_id
+                                        );
+                                    }
+                                    queue.remove(&job_id);
+                                }
                             }
-                            _ => unreachable!(),
-                        },
-                        Err(_error) => false,
+                            Ok(_) => unreachable!(),
+                            Err(TryRecvError::Disconnected) => job_ids.clear(),
+                            Err(TryRecvError::Empty) => {}
+                        }
+                        life_groups.retain(|(_, job_ids)| !job_ids.is_empty());
                     }
-                } else {
-                    false
-                };
+                    None => {}
+                }
 
-                // if the job_id key is not in the hashmap then attempt to schedule it
-                // if scheduling is a success then add the key and handle to the hashmap
+                while let Some((job_id, fire_time)) = queue.pop_due() {
+                    cronframe.fire_job(job_id, fire_time, &mut queue);
+                    cronframe.settle_job(job_id, &mut queue);
+                }
 
-                let mut job_handles = cronframe
-                    .job_handles
-                    .lock()
-                    .expect("job handles unwrap error in scheduler");
+                while let Some((job_id, run_id)) = queue.pop_due_retry() {
+                    cronframe.retry_run(job_id, run_id);
+                    cronframe.settle_job(job_id, &mut queue);
+                }
+            }
+        };
+
+        *self
+            .scheduler_handle
+            .lock()
+            .expect("scheduler handle unwrap error in start_scheduler method") =
+            Some(std::thread::spawn(scheduler));
+        info!("CronFrame Scheduler Running");
+        cronframe_return
+    }
 
-                // check if the daily timeout expired and reset it if need be
-                cron_job.reset_timeout();
+    // it puts the first fire time of a job after the given time in the queue and watches its life channel
+    // the runs missed while the process was down are caught up the first time a restored job is enqueued
+    fn enqueue_job(
+        &self,
+        cron_job: &mut CronJob,
+        after: DateTime<Utc>,
+        queue: &mut ScheduleQueue,
+        life_groups: &mut Vec<(Receiver<SchedulerMessage>, Vec<Uuid>)>,
+    ) {
+        let filter = self.config.scheduler.job_filter;
 
-                // if there is no handle for the job see if it needs to be scheduled
-                if !job_handles.contains_key(&job_id) && !to_be_dropped {
-                    if cron_job.suspended() {
-                        continue;
-                    }
+        // handle the job only if filter and job type match
+        // No filter -> all job types are to be executed
+        if filter != CronFilter::None && cron_job.type_filter() != filter {
+            return;
+        }
 
-                    // if the job timed-out than skip to the next job
-                    if cron_job.check_timeout() {
-                        if !cron_job.timeout_notified() {
-                            info!(
-                                "job name@{} - uuid#{} - Reached Timeout",
-                                cron_job.name(),
-                                job_id
-                            );
-                            cron_job.notify_timeout();
-                        }
-                        continue;
+        let after = match self.catch_up(cron_job) {
+            Some(last) => after.max(last),
+            None => after,
+        };
+
+        let next = match cron_job.trigger() {
+            // a one-shot job still fires if its time went by while the scheduler was stopped
+            Trigger::Once(at) if cron_job.last_fire().is_none() => Some(*at),
+            _ => cron_job.next_schedule(after),
+        };
+        match next {
+            Some(next) => queue.push(cron_job.id(), next),
+            None => queue.remove(&cron_job.id()),
+        }
+
+        // retries still waiting from before the scheduler was stopped
+        for (run_id, retry_at) in cron_job.pending_retries() {
+            queue.push_retry(cron_job.id(), run_id, retry_at);
+        }
+
+        if let Some((_, life_rx)) = cron_job.life_channels() {
+            match life_groups
+                .iter_mut()
+                .find(|(group_rx, _)| group_rx.same_channel(&life_rx))
+            {
+                Some((_, job_ids)) => {
+                    if !job_ids.contains(&cron_job.id()) {
+                        job_ids.push(cron_job.id());
                     }
+                }
+                None => life_groups.push((life_rx, vec![cron_job.id()])),
+            }
+        }
+    }
+
+    // it runs the fire times missed since the stored last fire time according to the catch-up policy
+    // it returns the latest missed fire time, None if there is nothing to catch up
+    fn catch_up(&self, cron_job: &mut CronJob) -> Option<DateTime<Utc>> {
+        let missed = cron_job.take_catch_up()?;
+        let last = *missed.last()?;
+
+        let policy = cron_job
+            .catch_up_policy()
+            .unwrap_or(self.config.scheduler.catch_up);
+        let runs = policy.runs(&missed);
+
+        info!(
+            "job name@{} - uuid#{} - Catch Up {} of {} missed runs - {}",
+            cron_job.name(),
+            cron_job.id(),
+            runs.len(),
+            missed.len(),
+            policy
+        );
+
+        cron_job.set_last_fire(last);
+        if let Some((&first, rest)) = runs.split_first() {
+            for time in rest {
+                cron_job.push_pending_run(*time);
+            }
+            self.start_run(cron_job, first);
+        }
+        self.persist(cron_job);
+
+        Some(last)
+    }
 
-                    let handle = (*cron_job).try_schedule(cronframe.config.scheduler.grace);
+    fn handle_message(
+        &self,
+        job_id: Uuid,
+        message: SchedulerMessage,
+        queue: &mut ScheduleQueue,
+        life_groups: &mut Vec<(Receiver<SchedulerMessage>, Vec<Uuid>)>,
+    ) {
+        let mut cron_jobs = self
+            .job_pool
+            .lock()
+            .expect("cron jobs unwrap error in scheduler");
+
+        match message {
+            SchedulerMessage::JobReschedule => match cron_jobs.get_mut(&job_id) {
+                Some(cron_job) => self.enqueue_job(cron_job, Utc::now(), queue, life_groups),
+                None => queue.remove(&job_id),
+            },
+            SchedulerMessage::JobComplete { run_id, start_time }
+            | SchedulerMessage::JobAbort { run_id, start_time }
+            | SchedulerMessage::JobTimedOut { run_id, start_time }
+            | SchedulerMessage::JobOverrun { run_id, start_time }
+            | SchedulerMessage::JobFailed {
+                run_id, start_time, ..
+            } => {
+                // the job might have been dropped while running
+                let Some(cron_job) = cron_jobs.get_mut(&job_id) else {
+                    return;
+                };
 
-                    if handle.is_some() {
-                        job_handles.insert(
-                            job_id.clone(),
-                            handle.expect("job handle unwrap error after try_schedule"),
+                let overrun = matches!(message, SchedulerMessage::JobOverrun { .. });
+                let (outcome, error) = match message {
+                    SchedulerMessage::JobFailed { error, .. } => {
+                        info!(
+                            "job name@{} - uuid#{} - run_uuid#{} - Failed - {}",
+                            cron_job.name(),
+                            job_id,
+                            run_id,
+                            error
                         );
+                        (RunOutcome::Failed, Some(error))
+                    }
+                    SchedulerMessage::JobComplete { .. } => {
                         info!(
-                            "job name@{} - uuid#{} - run_uuid#{} - Scheduled",
+                            "job name@{} - uuid#{} - run_uuid#{} - Completed",
                             cron_job.name(),
                             job_id,
-                            cron_job.run_id()
+                            run_id
                         );
+                        (RunOutcome::Completed, None)
                     }
-                }
-                // the job is in the hashmap and running
-                // check to see if it sent a message that says it finished or aborted
-                else if let Some((_, status_rx)) = cron_job.status_channels() {
-                    match status_rx.try_recv() {
-                        Ok(message) => match message {
-                            SchedulerMessage::JobComplete => {
-                                info!(
-                                    "job name@{} - uuid#{} - run_uuid#{} - Completed",
-                                    cron_job.name(),
-                                    job_id,
-                                    cron_job.run_id()
-                                );
-                                job_handles.remove(job_id);
-                                cron_job.clear_run_id();
-                            }
-                            SchedulerMessage::JobAbort => {
-                                info!(
-                                    "job name@{} - uuid#{} - run_uuid#{} - Aborted",
-                                    cron_job.name(),
-                                    job_id,
-                                    cron_job.run_id()
-                                );
-                                job_handles.remove(job_id);
-                                cron_job.clear_run_id();
-                                cron_job.fail();
-                            }
-                            _ => unreachable!(),
-                        },
-                        Err(_error) => {}
+                    SchedulerMessage::JobTimedOut { .. } | SchedulerMessage::JobOverrun { .. } => {
+                        info!(
+                            "job name@{} - uuid#{} - run_uuid#{} - Max Runtime Exceeded",
+                            cron_job.name(),
+                            job_id,
+                            run_id
+                        );
+                        (RunOutcome::TimedOut, None)
                     }
+                    _ => {
+                        info!(
+                            "job name@{} - uuid#{} - run_uuid#{} - Aborted",
+                            cron_job.name(),
+                            job_id,
+                            run_id
+                        );
+                        (RunOutcome::Aborted, None)
+                    }
+                };
+
+                if let Some(record) = cron_job.record_run(
+                    &run_id,
+                    start_time,
+                    outcome,
+                    error.clone(),
+                    self.config.scheduler.history_size,
+                ) {
+                    self.persist_run(cron_job, &record);
+                }
+
+                // the retry of an overrun attempt waits for its worker to return
+                // so that two attempts of the same run never execute at once
+                if overrun && cron_job.hold_overrun(&run_id) {
+                    return;
                 }
+
+                self.end_attempt(cron_job, run_id, outcome, error, queue);
             }
+            SchedulerMessage::JobReturned { run_id } => {
+                let Some(cron_job) = cron_jobs.get_mut(&job_id) else {
+                    return;
+                };
+                if cron_job.release_overrun(&run_id) {
+                    self.end_attempt(cron_job, run_id, RunOutcome::TimedOut, None, queue);
+                }
+            }
+            SchedulerMessage::JobCancelled { run_id } => {
+                let Some(cron_job) = cron_jobs.get_mut(&job_id) else {
+                    return;
+                };
+                info!(
+                    "job name@{} - uuid#{} - run_uuid#{} - Not Started",
+                    cron_job.name(),
+                    job_id,
+                    run_id
+                );
+                cron_job.finish_run(&run_id);
 
-            let mut pool = cron_jobs;
-            // drop function or methods jobs here
-            for job_id in jobs_to_drop {
-                pool.remove(&job_id);
+                if let Some(fire_time) = cron_job.pop_pending_run() {
+                    self.start_run(cron_job, fire_time);
+                }
             }
+            SchedulerMessage::SchedulerWake => {}
+            SchedulerMessage::JobDrop => unreachable!(),
+        }
+    }
+
+    // it retries an attempt that did not complete or ends its run
+    // a failed run keeps its slot while waiting for a retry
+    // the job is marked as failed only once the last attempt fails
+    fn end_attempt(
+        &self,
+        cron_job: &mut CronJob,
+        run_id: Uuid,
+        outcome: RunOutcome,
+        error: Option<String>,
+        queue: &mut ScheduleQueue,
+    ) {
+        if outcome != RunOutcome::Completed {
+            if let Some(retry_at) = cron_job.schedule_retry(&run_id) {
+                info!(
+                    "job name@{} - uuid#{} - run_uuid#{} - Retry - Attempt {}/{} at {}",
+                    cron_job.name(),
+                    cron_job.id(),
+                    run_id,
+                    cron_job.attempt(&run_id),
+                    cron_job.retry_policy().retries + 1,
+                    retry_at
+                );
+                queue.push_retry(cron_job.id(), run_id, retry_at);
+                return;
+            }
+            cron_job.fail();
+            if let Some(error) = error {
+                cron_job.set_last_error(error);
+            }
+        }
+        cron_job.finish_run(&run_id);
+
+        // queued runs and missed runs of a FireAll job take the freed up slot
+        if let Some(fire_time) = cron_job.pop_pending_run() {
+            self.start_run(cron_job, fire_time);
+        }
+    }
+
+    // it takes care of a job whose runs are over once the scheduler has handled it
+    // a one-shot job leaves the job pool and the job store, its run history stays available through history
+    // a job with a delay trigger is queued again after its delay
+    fn settle_job(&self, job_id: Uuid, queue: &mut ScheduleQueue) {
+        let mut cron_jobs = self
+            .job_pool
+            .lock()
+            .expect("cron jobs unwrap error in settle_job");
+
+        let Some(cron_job) = cron_jobs.get(&job_id) else {
+            return;
         };
 
-        std::thread::spawn(scheduler);
-        info!("CronFrame Scheduler Running");
-        cronframe_return
+        if cron_job.trigger().is_delay() {
+            let idle = cron_job.run_ids().is_empty() && cron_job.pending_runs() == 0;
+            // a job that never fired is either filtered out or already queued
+            if idle && cron_job.last_fire().is_some() && !queue.contains(&job_id) {
+                if let Some(next) = cron_job.next_schedule(Utc::now()) {
+                    queue.push(job_id, next);
+                }
+            }
+            return;
+        }
+
+        if !cron_job.fired_once() {
+            return;
+        }
+        let Some(cron_job) = cron_jobs.remove(&job_id) else {
+            return;
+        };
+
+        info!("job name@{} - uuid#{} - Archived", cron_job.name(), job_id);
+
+        self.unpersist(&cron_job);
+
+        self.archive
+            .lock()
+            .expect("archive unwrap error in settle_job")
+            .insert(job_id, cron_job.history());
+    }
+
+    // it runs a job whose fire time has been reached and queues its next fire time
+    // runs the scheduler is late for beyond the grace period are handled by the misfire policy
+    fn fire_job(&self, job_id: Uuid, fire_time: DateTime<Utc>, queue: &mut ScheduleQueue) {
+        let mut cron_jobs = self
+            .job_pool
+            .lock()
+            .expect("cron jobs unwrap error in scheduler");
+
+        let Some(cron_job) = cron_jobs.get_mut(&job_id) else {
+            return;
+        };
+
+        let now = Utc::now();
+        let grace = Duration::milliseconds(self.config.scheduler.grace.into());
+
+        let missed = cron_job.missed_schedules(fire_time);
+        let (on_time, misfired): (Vec<DateTime<Utc>>, Vec<DateTime<Utc>>) =
+            missed.iter().partition(|time| now - **time <= grace);
+
+        for time in &misfired {
+            info!(
+                "job name@{} - uuid#{} - Misfire {} - {:?}",
+                cron_job.name(),
+                job_id,
+                time,
+                cron_job.misfire_policy()
+            );
+        }
+
+        // the fire times of the runs to execute
+        let runs = match cron_job.misfire_policy() {
+            MisfirePolicy::FireOnce => missed.last().into_iter().copied().collect(),
+            MisfirePolicy::FireAll => missed.clone(),
+            MisfirePolicy::Skip => on_time,
+        };
+
+        if let Some(last) = missed.last() {
+            cron_job.set_last_fire(*last);
+            self.persist(cron_job);
+        }
+
+        // a delay trigger is queued again by settle_job once the runs are over
+        let latest = missed.last().copied().unwrap_or(fire_time);
+        if !cron_job.trigger().is_delay() {
+            if let Some(next) = cron_job.next_schedule(latest) {
+                queue.push(job_id, next);
+            }
+        }
+
+        let Some((&first, rest)) = runs.split_first() else {
+            return;
+        };
+
+        // the overlap policy decides about a due run while previous ones are still going
+        let policy = cron_job.overlap_policy();
+        if cron_job.run_ids().len() >= policy.max_instances() {
+            match policy {
+                OverlapPolicy::Queue => {
+                    for time in &runs {
+                        cron_job.push_pending_run(*time);
+                    }
+                    info!(
+                        "job name@{} - uuid#{} - Overlap Queued - {}",
+                        cron_job.name(),
+                        job_id,
+                        policy
+                    );
+                }
+                OverlapPolicy::Forbid | OverlapPolicy::Allow(_) => {
+                    info!(
+                        "job name@{} - uuid#{} - Overlap Skipped - {}",
+                        cron_job.name(),
+                        job_id,
+                        policy
+                    );
+                }
+            }
+            return;
+        }
+
+        for time in rest {
+            cron_job.push_pending_run(*time);
+        }
+        self.start_run(cron_job, first);
+    }
+
+    // it executes the next attempt of a failed run, unless the run was cancelled in the meantime
+    fn retry_run(&self, job_id: Uuid, run_id: Uuid) {
+        let mut cron_jobs = self
+            .job_pool
+            .lock()
+            .expect("cron jobs unwrap error in scheduler");
+
+        let Some(cron_job) = cron_jobs.get_mut(&job_id) else {
+            return;
+        };
+
+        match cron_job.start_retry(&run_id) {
+            Some(true) => {
+                self.worker_pool
+                    .submit(cron_job.job_run(run_id), cron_job.priority());
+            }
+            Some(false) => {
+                cron_job.finish_run(&run_id);
+                if let Some(fire_time) = cron_job.pop_pending_run() {
+                    self.start_run(cron_job, fire_time);
+                }
+            }
+            None => {}
+        }
+    }
+
+    // it starts a run of the job for the given fire time unless it is suspended or timed-out
+    fn start_run(&self, cron_job: &mut CronJob, fire_time: DateTime<Utc>) {
+        let job_id = cron_job.id();
+
+        // check if the daily timeout expired and reset it if need be
+        cron_job.reset_timeout();
+
+        if cron_job.suspended() {
+            cron_job.clear_pending_runs();
+            return;
+        }
+
+        // if the job timed-out than skip to the next job
+        if cron_job.check_timeout() {
+            if !cron_job.timeout_notified() {
+                info!(
+                    "job name@{} - uuid#{} - Reached Timeout",
+                    cron_job.name(),
+                    job_id
+                );
+                cron_job.notify_timeout();
+            }
+            cron_job.clear_pending_runs();
+            return;
+        }
+
+        let run_id = cron_job.new_run(fire_time, RunOrigin::Scheduled);
+        self.worker_pool
+            .submit(cron_job.job_run(run_id), cron_job.priority());
+        info!(
+            "job name@{} - uuid#{} - run_uuid#{} - Scheduled",
+            cron_job.name(),
+            job_id,
+            run_id
+        );
+    }
+
+    /// Recorded runs of a job from the oldest to the latest, empty if there is no such job
+    ///
+    /// The number of runs kept per job is set by `history_size` in the cronframe.toml,
+    /// the runs of a one-shot job are still available once it has left the job pool.
+    pub fn history(&self, job_id: Uuid) -> Vec<RunRecord> {
+        if let Some(cron_job) = self
+            .job_pool
+            .lock()
+            .expect("cron jobs unwrap error in history")
+            .get(&job_id)
+        {
+            return cron_job.history();
+        }
+
+        self.archive
+            .lock()
+            .expect("archive unwrap error in history")
+            .get(&job_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Number of worker threads executing the jobs
+    pub fn workers(&self) -> usize {
+        self.worker_pool.size()
+    }
+
+    /// Number of workers currently executing a run
+    pub fn busy_workers(&self) -> usize {
+        self.worker_pool.busy()
+    }
+
+    /// Number of due runs waiting for a free worker
+    pub fn queue_depth(&self) -> usize {
+        self.worker_pool.queued()
+    }
+
+    /// It notifies the scheduler that a job in the pool changed its schedule.
+    /// To be called after editing a job obtained through the `jobs` method.
+    pub fn reschedule(&self, job_id: Uuid) {
+        let _ = self
+            .scheduler_channels
+            .0
+            .send((job_id, SchedulerMessage::JobReschedule));
+    }
+
+    /// It runs a job right away, outside of its schedule, and returns the id of the run
+    ///
+    /// The run is recorded in the history of the job with a `Manual` origin,
+    /// it ignores the overlap policy and the daily timeout of the job but not its suspension.
+    /// None if there is no such job or the job is suspended.
+    pub fn trigger(&self, job_id: Uuid) -> Option<Uuid> {
+        let mut cron_jobs = self
+            .job_pool
+            .lock()
+            .expect("cron jobs unwrap error in trigger");
+
+        let cron_job = cron_jobs.get_mut(&job_id)?;
+        if cron_job.suspended() {
+            return None;
+        }
+
+        let run_id = cron_job.new_run(Utc::now(), RunOrigin::Manual);
+        self.worker_pool
+            .submit(cron_job.job_run(run_id), cron_job.priority());
+        info!(
+            "job name@{} - uuid#{} - run_uuid#{} - Manual Run",
+            cron_job.name(),
+            job_id,
+            run_id
+        );
+        Some(run_id)
     }
 
     /// This function can be used to keep the main thread alive after the scheduler has been started
@@ -331,12 +1181,27 @@ impl CronFrame {
     }
 
     /// It quits the running scheduler instance
+    /// It also cancels the runs that are going, jobs can observe it through their `JobContext`
     pub fn stop_scheduler(self: &Arc<Self>) {
         info!("CronFrame Scheduler Shutdown");
         *self.running.lock().unwrap() = false;
+
+        for cron_job in self
+            .job_pool
+            .lock()
+            .expect("cron jobs unwrap error in stop_scheduler method")
+            .values()
+        {
+            cron_job.cancel_runs();
+        }
+
+        let _ = self
+            .scheduler_channels
+            .0
+            .send((Uuid::nil(), SchedulerMessage::SchedulerWake));
     }
 
-    pub fn config(&self) -> &ConfigData{
+    pub fn config(&self) -> &ConfigData {
         &self.config
     }
 
@@ -344,18 +1209,12 @@ impl CronFrame {
         self.stop_scheduler();
         info!("CronFrame Shutdown");
 
-        // wait for job handlers to finish
-        let cronframe = self.clone();
-
-        let handles = cronframe
-            .job_handles
-            .lock()
-            .expect("job handles unwrap error in stop scheduler method");
+        // wait for the workers to finish the queued and running jobs
+        self.worker_pool.wait_idle();
 
-        for handle in handles.iter() {
-            while !handle.1.is_finished() {
-                // do some waiting until all job threads have terminated
-            }
+        // and for the job store to be up to date
+        if let Some(store) = &self.store {
+            store.flush();
         }
 
         // quit the web server
@@ -28,6 +28,7 @@ impl CronFrameExpr {
     ///     let my_expr = CronFrameExpr::new("0", "5", "10-14", "*", "*", "Sun", "*", 0);
     /// }
     /// ```
+    #[allow(clippy::too_many_arguments)]
     pub fn new(s: &str, m: &str, h: &str, dm: &str, mth: &str, dw: &str, y: &str, t: u64) -> Self {
         CronFrameExpr {
             seconds: s.to_string(),
@@ -1,9 +1,26 @@
-use crate::{cronframe::SchedulerMessage, utils};
+use crate::{
+    cancellation::CancellationToken,
+    cronframe::SchedulerMessage,
+    history::{RunHistory, RunOrigin, RunOutcome, RunRecord},
+    job_context::JobContext,
+    job_store::JobOverrides,
+    trigger::Trigger,
+    utils,
+};
 use chrono::{DateTime, Duration, Local, Utc};
-use cron::Schedule;
+use chrono_tz::Tz;
 use crossbeam_channel::{Receiver, Sender};
-use rocket::serde::Deserialize;
-use std::{any::Any, process::Command, str::FromStr, sync::Arc, thread::JoinHandle};
+use rocket::{serde::Deserialize, tokio::runtime::Handle};
+use std::{
+    any::Any,
+    collections::{HashMap, VecDeque},
+    future::Future,
+    panic::{self, AssertUnwindSafe},
+    pin::Pin,
+    process::Command,
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
 use uuid::Uuid;
 
 #[derive(Debug, Clone)]
@@ -12,27 +29,98 @@ pub struct CronJob {
     name: String,
     job: CronJobType,
     suspended: bool,
-    schedule: Schedule,
+    trigger: Trigger,
     timeout: Option<Duration>,
     timeout_notified: bool,
-    status_channels: Option<(Sender<SchedulerMessage>, Receiver<SchedulerMessage>)>,
     life_channels: Option<(Sender<SchedulerMessage>, Receiver<SchedulerMessage>)>,
     start_time: Option<DateTime<Utc>>,
-    run_id: Option<Uuid>,
+    run_ids: Vec<Uuid>,
+    runs: HashMap<Uuid, RunState>,
     failed: bool,
+    last_error: Option<String>,
+    misfire_policy: MisfirePolicy,
+    overlap_policy: OverlapPolicy,
+    last_fire: Option<DateTime<Utc>>,
+    pending_runs: VecDeque<DateTime<Utc>>,
+    priority: i32,
+    max_runtime: Option<Duration>,
+    retry_policy: RetryPolicy,
+    history: RunHistory,
+    // values changed at runtime, persisted by the job store
+    overrides: JobOverrides,
+    // None to follow the catch-up policy of the scheduler
+    catch_up_policy: Option<CatchUpPolicy>,
+    // the last fire time before a restart, taken when the job is first scheduled
+    catch_up_from: Option<DateTime<Utc>>,
+    // None until the time zone of the scheduler is applied when the job is added
+    timezone: Option<Tz>,
+    tags: Vec<String>,
 }
 
+// data of a run that is going, a failed run waiting to be retried keeps its state
 #[derive(Debug, Clone)]
+struct RunState {
+    token: CancellationToken,
+    fire_time: DateTime<Utc>,
+    attempt: u32,
+    retry_at: Option<DateTime<Utc>>,
+    origin: RunOrigin,
+    // the attempt is past its max runtime and its worker has not returned yet
+    overrun: bool,
+}
+
+// a run submitted to the worker pool, it carries only what executing the job needs
+#[derive(Debug)]
+pub(crate) struct JobRun {
+    job_id: Uuid,
+    name: String,
+    job: CronJobType,
+    run_id: Uuid,
+    max_runtime: Option<Duration>,
+    retry_policy: RetryPolicy,
+    state: RunState,
+}
+
+/// Future of an async job, executed on the shared async runtime
+pub type JobFuture = Pin<Box<dyn Future<Output = Result<(), String>> + Send>>;
+
+/// Function of a global or function job, with or without a `JobContext` argument
+///
+/// The fallible variants mark the run as failed when returning an error.
+#[derive(Debug, Clone, Copy)]
+pub enum JobFn {
+    Plain(fn()),
+    Context(fn(&JobContext)),
+    Fallible(fn() -> Result<(), String>),
+    FallibleContext(fn(&JobContext) -> Result<(), String>),
+    Async(fn(JobContext) -> JobFuture),
+}
+
+/// Function of a method job, with or without a `JobContext` argument
+///
+/// The fallible variants mark the run as failed when returning an error.
+#[derive(Debug, Clone, Copy)]
+#[allow(clippy::type_complexity)]
+pub enum MethodFn {
+    Plain(fn(Arc<Box<dyn Any + Send + Sync>>)),
+    Context(fn(Arc<Box<dyn Any + Send + Sync>>, &JobContext)),
+    Fallible(fn(Arc<Box<dyn Any + Send + Sync>>) -> Result<(), String>),
+    FallibleContext(fn(Arc<Box<dyn Any + Send + Sync>>, &JobContext) -> Result<(), String>),
+    Async(fn(Arc<Box<dyn Any + Send + Sync>>, JobContext) -> JobFuture),
+}
+
+#[derive(Debug, Clone)]
+#[allow(clippy::upper_case_acronyms)]
 pub enum CronJobType {
     Global {
-        job: fn(),
+        job: JobFn,
     },
     Function {
-        job: fn(),
+        job: JobFn,
     },
     Method {
         instance: Arc<Box<dyn Any + Send + Sync>>,
-        job: fn(Arc<Box<dyn Any + Send + Sync>>),
+        job: MethodFn,
     },
     CLI {
         job_name: String,
@@ -41,6 +129,7 @@ pub enum CronJobType {
 
 #[derive(Debug, PartialEq, Clone, Copy, Deserialize)]
 #[serde(crate = "rocket::serde")]
+#[allow(clippy::upper_case_acronyms)]
 pub enum CronFilter {
     None,
     Global,
@@ -49,26 +138,257 @@ pub enum CronFilter {
     CLI,
 }
 
+/// What the scheduler does with the runs of a job that were missed beyond the grace period
+#[derive(Debug, PartialEq, Clone, Copy, Deserialize, Default)]
+#[serde(crate = "rocket::serde")]
+pub enum MisfirePolicy {
+    /// all missed runs are coalesced into a single run
+    #[default]
+    FireOnce,
+    /// every missed run is executed, one after the other
+    FireAll,
+    /// missed runs are not executed
+    Skip,
+}
+
+/// What the scheduler does with the runs missed while the process was down
+///
+/// It is evaluated once when a job restored from the job store is first scheduled.
+#[derive(Debug, PartialEq, Clone, Copy, Deserialize, Default)]
+#[serde(crate = "rocket::serde")]
+pub enum CatchUpPolicy {
+    /// missed runs are not executed
+    #[default]
+    Skip,
+    /// all missed runs are coalesced into a single run
+    FireOnce,
+    /// the latest missed runs are executed one after the other, up to the given number
+    FireAll(usize),
+}
+
+impl CatchUpPolicy {
+    // the fire times to execute out of the missed ones
+    pub fn runs(&self, missed: &[DateTime<Utc>]) -> Vec<DateTime<Utc>> {
+        match self {
+            Self::Skip => Vec::new(),
+            Self::FireOnce => missed.last().into_iter().copied().collect(),
+            Self::FireAll(limit) => missed[missed.len().saturating_sub(*limit)..].to_vec(),
+        }
+    }
+}
+
+impl std::fmt::Display for CatchUpPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Skip => write!(f, "Skip"),
+            Self::FireOnce => write!(f, "FireOnce"),
+            Self::FireAll(limit) => write!(f, "FireAll({limit})"),
+        }
+    }
+}
+
+impl FromStr for CatchUpPolicy {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim() {
+            "Skip" => Ok(Self::Skip),
+            "FireOnce" => Ok(Self::FireOnce),
+            other => other
+                .strip_prefix("FireAll(")
+                .and_then(|rest| rest.strip_suffix(')'))
+                .and_then(|limit| limit.trim().parse::<usize>().ok())
+                .map(Self::FireAll)
+                .ok_or_else(|| {
+                    format!("unknown catch-up policy \"{other}\", expected \"Skip\", \"FireOnce\" or \"FireAll(N)\"")
+                }),
+        }
+    }
+}
+
+/// What the scheduler does when a job is due while a previous run of it is still going
+#[derive(Debug, PartialEq, Clone, Copy, Deserialize, Default)]
+#[serde(crate = "rocket::serde")]
+pub enum OverlapPolicy {
+    /// the due run is skipped
+    #[default]
+    Forbid,
+    /// the due run is executed after the current one completes
+    Queue,
+    /// the due run is executed in parallel, up to the given number of instances
+    Allow(usize),
+}
+
+impl OverlapPolicy {
+    // how many runs of the job can be going at the same time
+    pub fn max_instances(&self) -> usize {
+        match self {
+            Self::Forbid | Self::Queue => 1,
+            Self::Allow(instances) => (*instances).max(1),
+        }
+    }
+}
+
+impl std::fmt::Display for OverlapPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Forbid => write!(f, "Forbid"),
+            Self::Queue => write!(f, "Queue"),
+            Self::Allow(instances) => write!(f, "Allow({instances})"),
+        }
+    }
+}
+
+/// Delay before retrying a failed run of a job
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Backoff {
+    /// the same delay before every retry
+    Fixed(Duration),
+    /// the delay doubles at every retry, starting from the given one
+    Exponential(Duration),
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self::Fixed(Duration::zero())
+    }
+}
+
+impl std::fmt::Display for Backoff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Fixed(delay) => write!(f, "fixed:{}ms", delay.num_milliseconds()),
+            Self::Exponential(delay) => write!(f, "exp:{}ms", delay.num_milliseconds()),
+        }
+    }
+}
+
+/// How many times a failed run of a job is retried and how long to wait in between
+///
+/// A run fails when it aborts, returns an error or exceeds the max runtime,
+/// the job is marked as failed only once the last attempt fails.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct RetryPolicy {
+    pub retries: u32,
+    pub backoff: Backoff,
+    /// the delay is randomised between half and the whole of its value
+    pub jitter: bool,
+}
+
+impl RetryPolicy {
+    // the delay before the retry following the given failed attempt
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let delay = match self.backoff {
+            Backoff::Fixed(delay) => delay,
+            Backoff::Exponential(delay) => delay * 2i32.pow(attempt.saturating_sub(1).min(16)),
+        };
+
+        if self.jitter {
+            let half = delay.num_milliseconds() / 2;
+            let random = (Uuid::new_v4().as_u128() % (half as u128 + 1)) as i64;
+            Duration::milliseconds(half + random)
+        } else {
+            delay
+        }
+    }
+}
+
+impl std::fmt::Display for RetryPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.retries == 0 {
+            return write!(f, "None");
+        }
+        write!(f, "{} retries - {} backoff", self.retries, self.backoff)?;
+        if self.jitter {
+            write!(f, " with jitter")?;
+        }
+        Ok(())
+    }
+}
+
 impl CronJobType {
-    pub fn run_job(&self) {
+    // it returns the error message of a fallible job that failed
+    // CLI jobs fail when exiting with a non-zero status
+    // async jobs are spawned on the given runtime while the calling worker waits for them
+    pub fn run_job(&self, ctx: &JobContext, runtime: &Handle) -> Result<(), String> {
         match self {
-            Self::Global { job } | Self::Function { job } => (job)(),
-            Self::Method { instance, job } => (job)(instance.clone()),
+            Self::Global { job } | Self::Function { job } => match job {
+                JobFn::Plain(job) => {
+                    (job)();
+                    Ok(())
+                }
+                JobFn::Context(job) => {
+                    (job)(ctx);
+                    Ok(())
+                }
+                JobFn::Fallible(job) => (job)(),
+                JobFn::FallibleContext(job) => (job)(ctx),
+                JobFn::Async(job) => Self::run_future((job)(ctx.clone()), runtime),
+            },
+            Self::Method { instance, job } => match job {
+                MethodFn::Plain(job) => {
+                    (job)(instance.clone());
+                    Ok(())
+                }
+                MethodFn::Context(job) => {
+                    (job)(instance.clone(), ctx);
+                    Ok(())
+                }
+                MethodFn::Fallible(job) => (job)(instance.clone()),
+                MethodFn::FallibleContext(job) => (job)(instance.clone(), ctx),
+                MethodFn::Async(job) => {
+                    Self::run_future((job)(instance.clone(), ctx.clone()), runtime)
+                }
+            },
             Self::CLI { job_name } => {
                 let home_dir = {
                     let tmp = home::home_dir().unwrap();
                     tmp.to_str().unwrap().to_owned()
                 };
-                let _build = Command::new(format!("./{}", job_name))
+                let mut child = Command::new(format!("./{}", job_name))
                     .current_dir(format!("{home_dir}/.cronframe/cli_jobs"))
-                    .status()
+                    .spawn()
                     .expect("process failed to execute");
+
+                // the child process is killed once the run is cancelled
+                loop {
+                    match child.try_wait() {
+                        Ok(Some(status)) if status.success() => return Ok(()),
+                        Ok(Some(status)) => return Err(format!("process exited with {status}")),
+                        Ok(None) if ctx.is_cancelled() => {
+                            let _ = child.kill();
+                            let _ = child.wait();
+                            return Err("process killed after the run was cancelled".to_string());
+                        }
+                        Ok(None) => std::thread::sleep(std::time::Duration::from_millis(100)),
+                        Err(error) => return Err(error.to_string()),
+                    }
+                }
             }
         }
     }
 
+    // a panic of the future is resumed on the worker so that the run is reported as aborted
+    fn run_future(future: JobFuture, runtime: &Handle) -> Result<(), String> {
+        match runtime.block_on(runtime.spawn(future)) {
+            Ok(result) => result,
+            Err(error) if error.is_panic() => panic::resume_unwind(error.into_panic()),
+            Err(error) => Err(error.to_string()),
+        }
+    }
+
     pub fn job_type(&self) -> String {
         match self {
+            Self::Global {
+                job: JobFn::Async(_),
+            } => "Async Global".to_string(),
+            Self::Function {
+                job: JobFn::Async(_),
+            } => "Async Function".to_string(),
+            Self::Method {
+                job: MethodFn::Async(_),
+                ..
+            } => "Async Method".to_string(),
             Self::Global { .. } => "Global".to_string(),
             Self::Function { .. } => "Function".to_string(),
             Self::Method { .. } => "Method".to_string(),
@@ -86,114 +406,404 @@ impl CronJobType {
     }
 }
 
+impl JobRun {
+    // it runs the job on the calling worker thread
+    // the outcome of the run is reported on the scheduler channel, a panic means the run aborted
+    // a run going past the max runtime of the job has its token cancelled and is reported as timed-out
+    pub fn run(self, scheduler_tx: Sender<(Uuid, SchedulerMessage)>, runtime: &Handle) {
+        let JobRun {
+            job_id,
+            name,
+            job,
+            run_id,
+            max_runtime,
+            retry_policy,
+            state,
+        } = self;
+        // a run cancelled while waiting for a worker is not started at all
+        // the deadline of a previous attempt does not count, only an explicit cancellation
+        if state.token.cancel_requested() {
+            let _ = scheduler_tx.send((job_id, SchedulerMessage::JobCancelled { run_id }));
+            return;
+        }
+
+        let start_time = Utc::now();
+
+        // the attempts of a run are logged under the same run id
+        if retry_policy.retries > 0 {
+            info!(
+                "job name@{} - uuid#{} - run_uuid#{} - Execution - Attempt {}/{}",
+                name,
+                job_id,
+                run_id,
+                state.attempt,
+                retry_policy.retries + 1
+            );
+        } else {
+            info!(
+                "job name@{} - uuid#{} - run_uuid#{} - Execution",
+                name, job_id, run_id
+            );
+        }
+
+        let token = state.token.clone();
+        if let Some(max_runtime) = max_runtime {
+            token.set_deadline(start_time + max_runtime);
+        }
+
+        let ctx = JobContext {
+            job_name: name,
+            job_id,
+            run_id,
+            fire_time: state.fire_time,
+            start_time,
+            attempt: state.attempt,
+            origin: state.origin,
+            token: state.token,
+        };
+
+        // the overrun is reported once the max runtime is over even if the job never checks its token
+        // so that the run frees its slot, the worker executing it stays busy until the job returns
+        // the flag is held while sending so that the return of the worker is always reported after it
+        let reported = Arc::new(Mutex::new(false));
+        let watchdog = max_runtime
+            .and_then(|max_runtime| max_runtime.to_std().ok())
+            .map(|max_runtime| {
+                let reported = reported.clone();
+                let scheduler_tx = scheduler_tx.clone();
+                runtime.spawn(async move {
+                    rocket::tokio::time::sleep(max_runtime).await;
+                    let mut reported = reported.lock().expect("run report unwrap error");
+                    if !*reported {
+                        *reported = true;
+                        let message = SchedulerMessage::JobOverrun { run_id, start_time };
+                        let _ = scheduler_tx.send((job_id, message));
+                    }
+                })
+            });
+
+        let outcome =
+            token.scope(|| panic::catch_unwind(AssertUnwindSafe(|| job.run_job(&ctx, runtime))));
+
+        if let Some(watchdog) = watchdog {
+            watchdog.abort();
+        }
+        if std::mem::replace(
+            &mut *reported.lock().expect("run report unwrap error"),
+            true,
+        ) {
+            let _ = scheduler_tx.send((job_id, SchedulerMessage::JobReturned { run_id }));
+            return;
+        }
+
+        let message = match outcome {
+            _ if token.timed_out() => SchedulerMessage::JobTimedOut { run_id, start_time },
+            Ok(Ok(())) => SchedulerMessage::JobComplete { run_id, start_time },
+            Ok(Err(error)) => SchedulerMessage::JobFailed {
+                run_id,
+                start_time,
+                error,
+            },
+            Err(_) => SchedulerMessage::JobAbort { run_id, start_time },
+        };
+
+        let _ = scheduler_tx.send((job_id, message));
+    }
+}
+
 impl CronJob {
     pub fn new_global(
         name: &str,
-        job: fn(),
-        schedule: Schedule,
+        job: JobFn,
+        trigger: impl Into<Trigger>,
         timeout: Option<Duration>,
     ) -> CronJob {
         CronJob {
             name: name.to_string(),
             id: Uuid::new_v4(),
             job: CronJobType::Global { job },
-            schedule,
+            trigger: trigger.into(),
             timeout,
             timeout_notified: false,
             life_channels: None,
-            status_channels: Some(crossbeam_channel::bounded(1)),
             start_time: None,
-            run_id: None,
+            run_ids: Vec::new(),
+            runs: HashMap::new(),
             failed: false,
+            last_error: None,
             suspended: false,
+            misfire_policy: MisfirePolicy::default(),
+            overlap_policy: OverlapPolicy::default(),
+            last_fire: None,
+            pending_runs: VecDeque::new(),
+            priority: 0,
+            max_runtime: None,
+            retry_policy: RetryPolicy::default(),
+            history: RunHistory::default(),
+            overrides: JobOverrides::default(),
+            catch_up_policy: None,
+            catch_up_from: None,
+            timezone: None,
+            tags: Vec::new(),
         }
     }
 
     pub fn new_function(
         name: &str,
-        job: fn(),
-        schedule: Schedule,
+        job: JobFn,
+        trigger: impl Into<Trigger>,
         timeout: Option<Duration>,
     ) -> CronJob {
         CronJob {
             name: name.to_string(),
             id: Uuid::new_v4(),
             job: CronJobType::Function { job },
-            schedule,
+            trigger: trigger.into(),
             timeout,
             timeout_notified: false,
-            status_channels: Some(crossbeam_channel::bounded(1)),
             life_channels: None,
             start_time: None,
-            run_id: None,
+            run_ids: Vec::new(),
+            runs: HashMap::new(),
             failed: false,
+            last_error: None,
             suspended: false,
+            misfire_policy: MisfirePolicy::default(),
+            overlap_policy: OverlapPolicy::default(),
+            last_fire: None,
+            pending_runs: VecDeque::new(),
+            priority: 0,
+            max_runtime: None,
+            retry_policy: RetryPolicy::default(),
+            history: RunHistory::default(),
+            overrides: JobOverrides::default(),
+            catch_up_policy: None,
+            catch_up_from: None,
+            timezone: None,
+            tags: Vec::new(),
         }
     }
 
     pub fn new_method(
         name: &str,
         instance: Arc<Box<dyn Any + Send + Sync>>,
-        job: fn(Arc<Box<dyn Any + Send + Sync>>),
-        schedule: Schedule,
+        job: MethodFn,
+        trigger: impl Into<Trigger>,
         timeout: Option<Duration>,
     ) -> CronJob {
         CronJob {
             name: name.to_string(),
             id: Uuid::new_v4(),
             job: CronJobType::Method { instance, job },
-            schedule,
+            trigger: trigger.into(),
             timeout,
             timeout_notified: false,
-            status_channels: Some(crossbeam_channel::bounded(1)),
             life_channels: None,
             start_time: None,
-            run_id: None,
+            run_ids: Vec::new(),
+            runs: HashMap::new(),
             failed: false,
+            last_error: None,
             suspended: false,
+            misfire_policy: MisfirePolicy::default(),
+            overlap_policy: OverlapPolicy::default(),
+            last_fire: None,
+            pending_runs: VecDeque::new(),
+            priority: 0,
+            max_runtime: None,
+            retry_policy: RetryPolicy::default(),
+            history: RunHistory::default(),
+            overrides: JobOverrides::default(),
+            catch_up_policy: None,
+            catch_up_from: None,
+            timezone: None,
+            tags: Vec::new(),
         }
     }
 
-    pub fn new_cli(name: &str, schedule: Schedule, timeout: Option<Duration>) -> CronJob {
+    pub fn new_cli(name: &str, trigger: impl Into<Trigger>, timeout: Option<Duration>) -> CronJob {
         CronJob {
             name: name.to_string(),
             id: Uuid::new_v4(),
             job: CronJobType::CLI {
                 job_name: name.to_string(),
             },
-            schedule,
+            trigger: trigger.into(),
             timeout,
             timeout_notified: false,
-            status_channels: Some(crossbeam_channel::bounded(1)),
             life_channels: None,
             start_time: None,
-            run_id: None,
+            run_ids: Vec::new(),
+            runs: HashMap::new(),
             failed: false,
+            last_error: None,
             suspended: false,
+            misfire_policy: MisfirePolicy::default(),
+            overlap_policy: OverlapPolicy::default(),
+            last_fire: None,
+            pending_runs: VecDeque::new(),
+            priority: 0,
+            max_runtime: None,
+            retry_policy: RetryPolicy::default(),
+            history: RunHistory::default(),
+            overrides: JobOverrides::default(),
+            catch_up_policy: None,
+            catch_up_from: None,
+            timezone: None,
+            tags: Vec::new(),
         }
     }
 
-    pub fn try_schedule(&mut self, _grace_period: u32) -> Option<JoinHandle<()>> {
-        if self.check_schedule() {
-            self.run_id = Some(Uuid::new_v4());
+    // it registers a new run of the job for the given fire time, to be executed by the worker pool
+    pub fn new_run(&mut self, fire_time: DateTime<Utc>, origin: RunOrigin) -> Uuid {
+        let run_id = Uuid::new_v4();
 
-            if self.start_time.is_none() {
-                self.start_time = Some(Utc::now());
-            }
+        if self.start_time.is_none() {
+            self.start_time = Some(Utc::now());
+        }
 
-            if let Ok(handle) = self.run() {
-                return Some(handle);
+        self.run_ids.push(run_id);
+        self.runs.insert(
+            run_id,
+            RunState {
+                token: CancellationToken::new(),
+                fire_time,
+                attempt: 1,
+                retry_at: None,
+                origin,
+                overrun: false,
+            },
+        );
+        run_id
+    }
+
+    // it schedules the retry of a failed run according to the retry policy of the job
+    // None if the run is out of attempts or was cancelled, the run is then to be finished
+    pub fn schedule_retry(&mut self, run_id: &Uuid) -> Option<DateTime<Utc>> {
+        if !self.retry_allowed(run_id) {
+            return None;
+        }
+        let policy = self.retry_policy;
+        let state = self.runs.get_mut(run_id)?;
+
+        let retry_at = Utc::now() + policy.delay(state.attempt);
+        state.attempt += 1;
+        state.retry_at = Some(retry_at);
+        Some(retry_at)
+    }
+
+    // false if the run is over, suspended, cancelled or out of attempts
+    fn retry_allowed(&self, run_id: &Uuid) -> bool {
+        self.runs.get(run_id).is_some_and(|state| {
+            !self.suspended
+                && !state.token.cancel_requested()
+                && state.attempt <= self.retry_policy.retries
+        })
+    }
+
+    // it keeps an overrun attempt that can be retried until its worker returns
+    // false if the run is to end or be retried right away
+    pub(crate) fn hold_overrun(&mut self, run_id: &Uuid) -> bool {
+        if !self.retry_allowed(run_id) {
+            return false;
+        }
+        match self.runs.get_mut(run_id) {
+            Some(state) => {
+                state.overrun = true;
+                true
             }
+            None => false,
+        }
+    }
 
-            // TODO add graceful period running logic
+    // true if the run was held until the worker of its overrun attempt returned
+    pub(crate) fn release_overrun(&mut self, run_id: &Uuid) -> bool {
+        self.runs
+            .get_mut(run_id)
+            .is_some_and(|state| std::mem::take(&mut state.overrun))
+    }
+
+    // it takes the retry of a run that is due, false if the run was cancelled in the meantime
+    // None if the run is not waiting for a retry
+    pub fn start_retry(&mut self, run_id: &Uuid) -> Option<bool> {
+        let suspended = self.suspended;
+        let state = self.runs.get_mut(run_id)?;
+        state.retry_at.take()?;
+        Some(!suspended && !state.token.cancel_requested())
+    }
+
+    // the runs waiting to be retried along with the time of their retry
+    pub fn pending_retries(&self) -> Vec<(Uuid, DateTime<Utc>)> {
+        self.runs
+            .iter()
+            .filter_map(|(run_id, state)| state.retry_at.map(|retry_at| (*run_id, retry_at)))
+            .collect()
+    }
+
+    // it records the outcome of the current attempt of a run in the history of the job
+    // the record is returned if the history keeps it
+    pub fn record_run(
+        &mut self,
+        run_id: &Uuid,
+        start_time: DateTime<Utc>,
+        outcome: RunOutcome,
+        error: Option<String>,
+        capacity: usize,
+    ) -> Option<RunRecord> {
+        let state = self.runs.get(run_id)?;
+
+        let record = RunRecord {
+            run_id: *run_id,
+            attempt: state.attempt,
+            fire_time: state.fire_time,
+            start_time,
+            end_time: Utc::now(),
+            outcome,
+            origin: state.origin,
+            error,
+        };
+        self.history.push(record.clone(), capacity);
+        (capacity > 0).then_some(record)
+    }
+
+    // the recorded runs of the job from the oldest to the latest
+    pub fn history(&self) -> Vec<RunRecord> {
+        self.history.records()
+    }
+
+    // it puts back the runs recorded before a restart
+    pub(crate) fn restore_history(
+        &mut self,
+        records: impl Iterator<Item = RunRecord>,
+        capacity: usize,
+    ) {
+        for record in records {
+            self.history.push(record, capacity);
         }
-        None
+    }
+
+    // the current attempt of a run, 1 for the first execution
+    pub fn attempt(&self, run_id: &Uuid) -> u32 {
+        self.runs.get(run_id).map_or(1, |state| state.attempt)
+    }
+
+    // returns the fire times from the given one up to now, these are the runs the scheduler is late for
+    pub fn missed_schedules(&self, fire_time: DateTime<Utc>) -> Vec<DateTime<Utc>> {
+        let now = Utc::now();
+        std::iter::once(fire_time)
+            .chain(self.trigger.between(&fire_time, &now, self.zone()))
+            .collect()
+    }
+
+    // returns the first fire time of the job strictly after the given time
+    pub fn next_schedule(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.trigger.after(&after, self.zone())
     }
 
     // checks if a job's upcoming schedule is within the next second
     pub fn check_schedule(&self) -> bool {
         let now = Utc::now();
-        if let Some(next) = self.schedule.upcoming(Utc).take(1).next() {
+        if let Some(next) = self.trigger.after(&now, self.zone()) {
             let until_next = (next - now).num_milliseconds();
             if until_next <= 1000 {
                 return true;
@@ -204,6 +814,7 @@ impl CronJob {
 
     // the expected value is in milliseconds
     pub fn set_timeout(&mut self, value: i64) {
+        self.overrides.timeout = Some(value);
         self.timeout = if value > 0 {
             self.start_time = None;
             Some(Duration::milliseconds(value))
@@ -218,7 +829,7 @@ impl CronJob {
             "Suspended".to_string()
         } else if self.check_timeout() {
             "Timed-Out".to_string()
-        } else if self.run_id.is_some() {
+        } else if !self.run_ids.is_empty() {
             "Running".to_string()
         } else {
             "Awaiting Schedule".to_string()
@@ -226,10 +837,12 @@ impl CronJob {
     }
 
     // method used by the web server the change the cron expression of a job
+    // an RFC 3339 date time turns the job into a one-shot job
     pub fn set_schedule(&mut self, expression: &str) -> bool {
         let expr = expression.replace("slh", "/").replace("%20", " ");
-        if let Ok(schedule) = Schedule::from_str(expr.as_str()) {
-            self.schedule = schedule;
+        if let Ok(trigger) = Trigger::from_str(expr.as_str()) {
+            self.overrides.cron_expr = Some(trigger.to_string());
+            self.trigger = trigger;
             return true;
         }
         false
@@ -237,16 +850,9 @@ impl CronJob {
 
     // returns true if timeout expired
     pub fn check_timeout(&self) -> bool {
-        if let Some(timeout) = self.timeout {
-            if self.start_time.is_some() {
-                let timeout = self
-                    .start_time
-                    .expect("start time unwrap error in check_timeout")
-                    + timeout;
-
-                if Utc::now() >= timeout {
-                    return true;
-                }
+        if let (Some(timeout), Some(start_time)) = (self.timeout, self.start_time) {
+            if Utc::now() >= start_time + timeout {
+                return true;
             }
         }
         false
@@ -254,31 +860,69 @@ impl CronJob {
 
     // it resets the timeout if 24h have passed
     pub fn reset_timeout(&mut self) {
-        if let Some(timeout) = self.timeout {
-            if self.start_time.is_some() {
-                let timeout = self
-                    .start_time
-                    .expect("start time unwrap error in timeout_reset")
-                    + timeout;
-
-                if Utc::now() >= timeout + Duration::hours(24) {
-                    self.start_time = None;
-                }
+        if let (Some(timeout), Some(start_time)) = (self.timeout, self.start_time) {
+            if Utc::now() >= start_time + timeout + Duration::hours(24) {
+                self.start_time = None;
             }
         }
     }
 
-    // get the schedule constructed from the cron expression
+    // get the schedule constructed from the cron expression, or the fire time of a one-shot job
     pub fn schedule(&self) -> String {
-        self.schedule.to_string()
+        self.trigger.to_string()
+    }
+
+    pub fn trigger(&self) -> &Trigger {
+        &self.trigger
+    }
+
+    // the time zone cron expressions are evaluated in, None if it is not set yet
+    pub fn timezone(&self) -> Option<Tz> {
+        self.timezone
+    }
+
+    pub fn set_timezone(&mut self, timezone: Option<Tz>) {
+        self.timezone = timezone;
+    }
+
+    // it applies the time zone of the scheduler to a job without one of its own
+    pub(crate) fn set_default_timezone(&mut self, timezone: Tz) {
+        self.timezone.get_or_insert(timezone);
+    }
+
+    // labels of the job, shown on the job page
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    pub fn set_tags(&mut self, tags: Vec<String>) {
+        self.tags = tags;
+    }
+
+    fn zone(&self) -> Tz {
+        self.timezone.unwrap_or(Tz::UTC)
+    }
+
+    pub fn set_trigger(&mut self, trigger: Trigger) {
+        self.overrides.cron_expr = Some(trigger.to_string());
+        self.trigger = trigger;
+    }
+
+    // true for a one-shot job that has fired and whose runs are over
+    pub fn fired_once(&self) -> bool {
+        self.trigger.is_once()
+            && self.last_fire.is_some()
+            && self.run_ids.is_empty()
+            && self.pending_runs.is_empty()
+    }
+
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
     }
 
     // it returns the timeout or "None" if a timeout is not set
     pub fn timeout_to_string(&self) -> String {
-        if self.timeout.is_some() {
-            let timeout = self
-                .timeout
-                .expect("timeout unwrap error in timeout_to_string");
+        if let Some(timeout) = self.timeout {
             format!(
                 "{} s \n {} ms",
                 timeout.num_seconds(),
@@ -296,86 +940,53 @@ impl CronJob {
 
     // if the job is active it returns the schedule otherwise a message telling why there is no next schedule
     pub fn upcoming_utc(&self) -> Option<DateTime<Utc>> {
-        self.schedule.upcoming(Utc).into_iter().next()
+        self.trigger.after(&Utc::now(), self.zone())
     }
 
     // if the job is active it returns the schedule otherwise a message telling why there is no next schedule
     pub fn upcoming_local(&self) -> Option<DateTime<Local>> {
-        if let Some(time) = self.schedule.upcoming(Utc).into_iter().next() {
-            Some(utils::utc_to_local_time(time))
-        } else {
-            None
-        }
+        self.upcoming_utc().map(utils::utc_to_local_time)
     }
 
-    // it returns the id of the current execution of the job, or "None" if it is not running
+    // the upcoming schedule in the time zone of the job
+    pub fn upcoming_zone(&self) -> Option<DateTime<Tz>> {
+        self.upcoming_utc()
+            .map(|datetime| datetime.with_timezone(&self.zone()))
+    }
+
+    // it returns the id of the latest execution of the job, or "None" if it is not running
     pub fn run_id(&self) -> String {
-        match &self.run_id {
+        match self.run_ids.last() {
             Some(uuid) => uuid.to_string(),
             None => "None".into(),
         }
     }
 
-    // this spawns a control thread for the job that spawns a thread with the actual job
-    pub fn run(&self) -> std::io::Result<JoinHandle<()>> {
-        let cron_job = self.clone();
-        let tx = self
-            .status_channels
-            .as_ref()
-            .expect("tx unwap error in job run method")
-            .0
-            .clone();
-        let _rx = self
-            .status_channels
-            .as_ref()
-            .expect("rx unwap error in job run method")
-            .1
-            .clone();
-        let schedule = self.schedule.clone();
-
-        let run_id = cron_job
-            .run_id
-            .as_ref()
-            .expect("run_id unwap error in job run method")
-            .clone();
-
-        // the actual job thread
-        // this is spawned form the control thread
-        // it gets the next schedule, waits up to it and runs the job
-        let job_thread = move || {
-            let now = Utc::now();
-            if let Some(next) = schedule.upcoming(Utc).take(1).next() {
-                let until_next = next - now;
-                std::thread::sleep(until_next.to_std().unwrap());
-
-                info!(
-                    "job name@{} - uuid#{} - run_uuid#{} - Execution",
-                    cron_job.name, cron_job.id, run_id
-                );
-                cron_job.job.run_job();
-            }
-        };
-
-        // the control thread handle is what gets returned to cronframe
-        // this allows to check for job completion or fail
-        let control_thread = move || {
-            let job_handle = std::thread::spawn(job_thread);
+    // the ids of all the executions of the job that are currently going
+    pub fn run_ids(&self) -> &[Uuid] {
+        &self.run_ids
+    }
 
-            while !job_handle.is_finished() {
-                std::thread::sleep(Duration::milliseconds(250).to_std().unwrap());
-            }
+    // the run with the given id as submitted to the worker pool
+    pub(crate) fn job_run(&self, run_id: Uuid) -> JobRun {
+        let state = self.runs.get(&run_id).cloned().unwrap_or(RunState {
+            token: CancellationToken::new(),
+            fire_time: Utc::now(),
+            attempt: 1,
+            retry_at: None,
+            origin: RunOrigin::Scheduled,
+            overrun: false,
+        });
 
-            match job_handle.join() {
-                Ok(_) => {
-                    let _ = tx.send(SchedulerMessage::JobComplete);
-                }
-                Err(_) => {
-                    let _ = tx.send(SchedulerMessage::JobAbort);
-                }
-            };
-        };
-
-        std::thread::Builder::spawn(std::thread::Builder::new(), control_thread)
+        JobRun {
+            job_id: self.id,
+            name: self.name.clone(),
+            job: self.job.clone(),
+            run_id,
+            max_runtime: self.max_runtime,
+            retry_policy: self.retry_policy,
+            state,
+        }
     }
 
     pub fn name(&self) -> String {
@@ -386,6 +997,11 @@ impl CronJob {
         self.id
     }
 
+    // used to keep the id of a job recreated from the job store
+    pub(crate) fn set_id(&mut self, id: Uuid) {
+        self.id = id;
+    }
+
     pub fn type_filter(&self) -> CronFilter {
         self.job.type_to_filter()
     }
@@ -394,22 +1010,23 @@ impl CronJob {
         self.life_channels.clone()
     }
 
-    pub fn add_life_channels(&mut self, channels: (Sender<SchedulerMessage>, Receiver<SchedulerMessage>)){
+    pub fn add_life_channels(
+        &mut self,
+        channels: (Sender<SchedulerMessage>, Receiver<SchedulerMessage>),
+    ) {
         self.life_channels = Some(channels);
     }
 
-    pub fn status_channels(
-        &self,
-    ) -> Option<(Sender<SchedulerMessage>, Receiver<SchedulerMessage>)> {
-        self.status_channels.clone()
-    }
-
     pub fn suspended(&self) -> bool {
         self.suspended
     }
 
+    // suspending a job also cancels its runs that are going
     pub fn suspension(&mut self, value: bool) {
         self.suspended = value;
+        if value {
+            self.cancel_runs();
+        }
     }
 
     pub fn timeout_notified(&self) -> bool {
@@ -420,8 +1037,30 @@ impl CronJob {
         self.timeout_notified = true;
     }
 
-    pub fn clear_run_id(&mut self) {
-        self.run_id = None;
+    // it removes a completed or aborted execution from the ones that are going
+    pub fn finish_run(&mut self, run_id: &Uuid) {
+        self.run_ids.retain(|id| id != run_id);
+        self.runs.remove(run_id);
+    }
+
+    // it trips the cancellation token of every run of the job that is going
+    pub fn cancel_runs(&self) {
+        for run_id in &self.run_ids {
+            if let Some(RunState { token, .. }) = self.runs.get(run_id) {
+                if !token.is_cancelled() {
+                    info!(
+                        "job name@{} - uuid#{} - run_uuid#{} - Cancelled",
+                        self.name, self.id, run_id
+                    );
+                }
+                token.cancel();
+            }
+        }
+    }
+
+    // the cancellation token of a run that is currently going
+    pub fn run_token(&self, run_id: &Uuid) -> Option<CancellationToken> {
+        self.runs.get(run_id).map(|state| state.token.clone())
     }
 
     pub fn failed(&self) -> bool {
@@ -431,4 +1070,115 @@ impl CronJob {
     pub fn fail(&mut self) {
         self.failed = true;
     }
+
+    // the error returned by the latest failed run of a fallible job
+    pub fn last_error(&self) -> Option<&str> {
+        self.last_error.as_deref()
+    }
+
+    pub fn set_last_error(&mut self, error: String) {
+        self.last_error = Some(error);
+    }
+
+    pub fn misfire_policy(&self) -> MisfirePolicy {
+        self.misfire_policy
+    }
+
+    pub fn set_misfire_policy(&mut self, policy: MisfirePolicy) {
+        self.misfire_policy = policy;
+    }
+
+    pub fn overlap_policy(&self) -> OverlapPolicy {
+        self.overlap_policy
+    }
+
+    pub fn set_overlap_policy(&mut self, policy: OverlapPolicy) {
+        self.overlap_policy = policy;
+    }
+
+    // the last fire time handled by the scheduler, runs missed since then are misfires
+    pub fn last_fire(&self) -> Option<DateTime<Utc>> {
+        self.last_fire
+    }
+
+    pub fn set_last_fire(&mut self, fire_time: DateTime<Utc>) {
+        self.last_fire = Some(fire_time);
+    }
+
+    pub fn catch_up_policy(&self) -> Option<CatchUpPolicy> {
+        self.catch_up_policy
+    }
+
+    pub fn set_catch_up_policy(&mut self, policy: Option<CatchUpPolicy>) {
+        self.catch_up_policy = policy;
+    }
+
+    // the last fire time before a restart, until the runs missed since then are caught up
+    pub fn catch_up_from(&self) -> Option<DateTime<Utc>> {
+        self.catch_up_from
+    }
+
+    pub(crate) fn set_catch_up_from(&mut self, fire_time: DateTime<Utc>) {
+        self.catch_up_from = Some(fire_time);
+    }
+
+    // it returns the fire times missed since the last fire time before a restart, only once
+    pub(crate) fn take_catch_up(&mut self) -> Option<Vec<DateTime<Utc>>> {
+        let from = self.catch_up_from.take()?;
+        Some(self.trigger.between(&from, &Utc::now(), self.zone()))
+    }
+
+    // number of runs waiting for a free slot of the job
+    pub fn pending_runs(&self) -> usize {
+        self.pending_runs.len()
+    }
+
+    pub fn push_pending_run(&mut self, fire_time: DateTime<Utc>) {
+        self.pending_runs.push_back(fire_time);
+    }
+
+    // the fire time of the earliest run waiting for a free slot of the job
+    pub fn pop_pending_run(&mut self) -> Option<DateTime<Utc>> {
+        self.pending_runs.pop_front()
+    }
+
+    pub fn clear_pending_runs(&mut self) {
+        self.pending_runs.clear();
+    }
+
+    // runs with a higher priority are picked first by the worker pool
+    pub fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    pub fn set_priority(&mut self, priority: i32) {
+        self.priority = priority;
+    }
+
+    pub fn retry_policy(&self) -> RetryPolicy {
+        self.retry_policy
+    }
+
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.retry_policy = policy;
+    }
+
+    pub fn set_retries(&mut self, retries: u32) {
+        self.overrides.retries = Some(retries);
+        self.retry_policy.retries = retries;
+    }
+
+    // the schedule, timeout and retries changed at runtime
+    pub fn overrides(&self) -> &JobOverrides {
+        &self.overrides
+    }
+
+    // time limit of a single run, unlike the timeout it is not a daily budget
+    pub fn max_runtime(&self) -> Option<Duration> {
+        self.max_runtime
+    }
+
+    pub fn set_max_runtime(&mut self, max_runtime: Option<Duration>) {
+        self.max_runtime = max_runtime;
+    }
 }
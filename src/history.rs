@@ -0,0 +1,93 @@
+use chrono::{DateTime, Duration, Utc};
+use rocket::serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use uuid::Uuid;
+
+/// How a run of a job ended
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+#[serde(crate = "rocket::serde")]
+pub enum RunOutcome {
+    Completed,
+    /// the job returned an error
+    Failed,
+    /// the job panicked
+    Aborted,
+    /// the run went past the max runtime of the job
+    TimedOut,
+}
+
+impl std::fmt::Display for RunOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Completed => write!(f, "Completed"),
+            Self::Failed => write!(f, "Failed"),
+            Self::Aborted => write!(f, "Aborted"),
+            Self::TimedOut => write!(f, "Max Runtime Exceeded"),
+        }
+    }
+}
+
+/// What started a run of a job
+#[derive(Debug, PartialEq, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(crate = "rocket::serde")]
+pub enum RunOrigin {
+    /// a fire time of the trigger of the job
+    #[default]
+    Scheduled,
+    /// a run requested through `CronFrame::trigger`, the web server or the CLI tool
+    Manual,
+}
+
+impl std::fmt::Display for RunOrigin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Scheduled => write!(f, "Scheduled"),
+            Self::Manual => write!(f, "Manual"),
+        }
+    }
+}
+
+/// Record of a finished attempt of a run, kept in the history of its job
+///
+/// The attempts of a retried run share the same run id.
+#[derive(Debug, Clone)]
+pub struct RunRecord {
+    pub run_id: Uuid,
+    pub attempt: u32,
+    pub fire_time: DateTime<Utc>,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub outcome: RunOutcome,
+    pub origin: RunOrigin,
+    /// the error returned by a fallible job
+    pub error: Option<String>,
+}
+
+impl RunRecord {
+    pub fn duration(&self) -> Duration {
+        self.end_time - self.start_time
+    }
+}
+
+// bounded history of the runs of a job, the oldest record is dropped once it is full
+// a capacity of zero disables the history
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RunHistory {
+    records: VecDeque<RunRecord>,
+}
+
+impl RunHistory {
+    pub fn push(&mut self, record: RunRecord, capacity: usize) {
+        while !self.records.is_empty() && self.records.len() >= capacity {
+            self.records.pop_front();
+        }
+        if capacity > 0 {
+            self.records.push_back(record);
+        }
+    }
+
+    // the records from the oldest to the latest
+    pub fn records(&self) -> Vec<RunRecord> {
+        self.records.iter().cloned().collect()
+    }
+}
@@ -1,35 +1,315 @@
-use crate::cronjob::CronJob;
+use crate::cronjob::{
+    Backoff, CatchUpPolicy, CronJob, JobFn, MethodFn, MisfirePolicy, OverlapPolicy, RetryPolicy,
+};
+use crate::job_context::JobContext;
+use crate::trigger::Trigger;
 use chrono::Duration;
-use cron::Schedule;
+use chrono_tz::Tz;
 use std::any::Any;
+use std::borrow::Cow;
 use std::str::FromStr;
 use std::sync::Arc;
 
+/// Error in the definition of a job, returned by `JobBuilder::build` and by the methods adding jobs
+#[derive(Debug, Clone, PartialEq)]
+pub enum CronFrameError {
+    /// the cron expression, or other trigger expression, of the job cannot be parsed
+    InvalidExpression {
+        name: String,
+        expression: String,
+        reason: String,
+    },
+    /// the timeout of the job is not a number of milliseconds
+    InvalidTimeout { name: String, timeout: String },
+    /// the name of the job is empty or it contains a character not allowed in the links of the web server
+    InvalidName(String),
+}
+
+impl std::fmt::Display for CronFrameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidExpression {
+                name,
+                expression,
+                reason,
+            } => write!(
+                f,
+                "job \"{name}\" - invalid expression \"{expression}\": {reason}"
+            ),
+            Self::InvalidTimeout { name, timeout } => write!(
+                f,
+                "job \"{name}\" - invalid timeout \"{timeout}\", expected a number of ms"
+            ),
+            Self::InvalidName(name) => write!(
+                f,
+                "invalid job name \"{name}\", it must not be blank or contain any of / \\ ? # %"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CronFrameError {}
+
+// names end up in the links of the job pages
+pub(crate) fn validate_name(name: &str) -> Result<(), CronFrameError> {
+    if name.trim().is_empty() || name.contains(['/', '\\', '?', '#', '%']) {
+        return Err(CronFrameError::InvalidName(name.to_string()));
+    }
+    Ok(())
+}
+
+// it parses the trigger and the timeout of a job, a timeout of 0 or less means no timeout
+fn parse_definition(
+    name: &str,
+    trigger: JobTrigger,
+    timeout: &str,
+) -> Result<(Trigger, Option<Duration>), CronFrameError> {
+    validate_name(name)?;
+
+    let invalid = |expression: String, reason: String| CronFrameError::InvalidExpression {
+        name: name.to_string(),
+        expression,
+        reason,
+    };
+    let trigger = match trigger {
+        JobTrigger::Expr(cron_expr) => Trigger::from_str(&cron_expr)
+            .map_err(|reason| invalid(cron_expr.to_string(), reason))?,
+        // the same check as for the durations of an expression
+        JobTrigger::Trigger(trigger) => match trigger {
+            Trigger::Interval(every) | Trigger::Delay(every) if every <= Duration::zero() => {
+                let reason = "the duration must be positive".to_string();
+                return Err(invalid(trigger.to_string(), reason));
+            }
+            trigger => trigger,
+        },
+    };
+
+    let timeout = match timeout.trim().parse::<i64>() {
+        Ok(timeout) if timeout > 0 => Duration::try_milliseconds(timeout),
+        Ok(_) => None,
+        Err(_) => {
+            return Err(CronFrameError::InvalidTimeout {
+                name: name.to_string(),
+                timeout: timeout.to_string(),
+            })
+        }
+    };
+
+    Ok((trigger, timeout))
+}
+
+/// Schedule of a job to build, either an expression parsed by `JobBuilder::build` or a `Trigger`
+///
+/// ```
+/// use cronframe::{Duration, JobBuilder, Trigger};
+///
+/// fn my_job() {}
+///
+/// let from_expr = JobBuilder::global_job("my_job", my_job, "every 90s", "0");
+/// let from_trigger = JobBuilder::global_job_with_trigger(
+///     "my_job",
+///     my_job,
+///     Trigger::Interval(Duration::seconds(90)),
+///     "0",
+/// );
+/// assert_eq!(
+///     from_expr.build().unwrap().trigger(),
+///     from_trigger.build().unwrap().trigger()
+/// );
+/// ```
+#[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
+pub enum JobTrigger<'a> {
+    /// a cron expression or any other expression accepted by `Trigger::from_str`
+    Expr(Cow<'a, str>),
+    Trigger(Trigger),
+}
+
+impl<'a> JobTrigger<'a> {
+    pub const fn expr(cron_expr: &'a str) -> Self {
+        JobTrigger::Expr(Cow::Borrowed(cron_expr))
+    }
+}
+
+impl<'a> From<&'a str> for JobTrigger<'a> {
+    fn from(cron_expr: &'a str) -> Self {
+        JobTrigger::expr(cron_expr)
+    }
+}
+
+impl From<String> for JobTrigger<'_> {
+    fn from(cron_expr: String) -> Self {
+        JobTrigger::Expr(Cow::Owned(cron_expr))
+    }
+}
+
+impl From<Trigger> for JobTrigger<'_> {
+    fn from(trigger: Trigger) -> Self {
+        JobTrigger::Trigger(trigger)
+    }
+}
+
+/// Per-job scheduling options, shared by every kind of job builder
+///
+/// ```
+/// use cronframe::{JobBuilder, JobOptions, MisfirePolicy};
+///
+/// fn my_job() {}
+///
+/// let job = JobBuilder::global_job("my_job", my_job, "0 0 3 * * * *", "0")
+///     .with_options(JobOptions::new().misfire(MisfirePolicy::Skip))
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct JobOptions {
+    pub misfire: MisfirePolicy,
+    pub overlap: OverlapPolicy,
+    pub priority: i32,
+    pub max_runtime: Option<Duration>,
+    pub retry: RetryPolicy,
+    pub catch_up: Option<CatchUpPolicy>,
+    pub timezone: Option<Tz>,
+    pub tags: &'static [&'static str],
+    pub suspended: bool,
+}
+
+impl JobOptions {
+    pub const fn new() -> Self {
+        JobOptions {
+            misfire: MisfirePolicy::FireOnce,
+            overlap: OverlapPolicy::Forbid,
+            priority: 0,
+            max_runtime: None,
+            retry: RetryPolicy {
+                retries: 0,
+                backoff: Backoff::Fixed(Duration::zero()),
+                jitter: false,
+            },
+            catch_up: None,
+            timezone: None,
+            tags: &[],
+            suspended: false,
+        }
+    }
+
+    // what to do with the runs missed beyond the grace period
+    pub const fn misfire(mut self, policy: MisfirePolicy) -> Self {
+        self.misfire = policy;
+        self
+    }
+
+    // what to do with a due run while previous runs are still going
+    pub const fn overlap(mut self, policy: OverlapPolicy) -> Self {
+        self.overlap = policy;
+        self
+    }
+
+    // due runs with a higher priority are executed first when all workers are busy
+    pub const fn priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    // time limit of a single run, the run is cancelled once it is exceeded
+    pub const fn max_runtime(mut self, max_runtime: Duration) -> Self {
+        self.max_runtime = Some(max_runtime);
+        self
+    }
+
+    // how many times a failed run is retried before the job is marked as failed
+    pub const fn retries(mut self, retries: u32) -> Self {
+        self.retry.retries = retries;
+        self
+    }
+
+    // the delay between the attempts of a failed run
+    pub const fn backoff(mut self, backoff: Backoff) -> Self {
+        self.retry.backoff = backoff;
+        self
+    }
+
+    // randomises the delay between the attempts of a failed run
+    pub const fn jitter(mut self, jitter: bool) -> Self {
+        self.retry.jitter = jitter;
+        self
+    }
+
+    // what to do with the runs missed while the process was down, instead of the scheduler default
+    pub const fn catch_up(mut self, policy: CatchUpPolicy) -> Self {
+        self.catch_up = Some(policy);
+        self
+    }
+
+    // the time zone cron expressions are evaluated in, instead of the scheduler default
+    pub const fn timezone(mut self, timezone: Tz) -> Self {
+        self.timezone = Some(timezone);
+        self
+    }
+
+    // labels of the job, shown on the job page
+    pub const fn tags(mut self, tags: &'static [&'static str]) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    // the job is added with its scheduling suspended, it can be reprised from the job page
+    pub const fn suspended(mut self, suspended: bool) -> Self {
+        self.suspended = suspended;
+        self
+    }
+
+    fn apply(&self, cron_job: &mut CronJob) {
+        cron_job.set_misfire_policy(self.misfire);
+        cron_job.set_overlap_policy(self.overlap);
+        cron_job.set_priority(self.priority);
+        cron_job.set_max_runtime(self.max_runtime);
+        cron_job.set_retry_policy(self.retry);
+        cron_job.set_catch_up_policy(self.catch_up);
+        cron_job.set_timezone(self.timezone);
+        cron_job.set_tags(self.tags.iter().map(|tag| tag.to_string()).collect());
+        if self.suspended {
+            cron_job.suspension(true);
+        }
+    }
+}
+
+impl Default for JobOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[derive(Debug, Clone)]
+#[allow(clippy::upper_case_acronyms)]
 pub enum JobBuilder<'a> {
     Global {
         name: &'a str,
-        job: fn(),
-        cron_expr: &'a str,
+        job: JobFn,
+        trigger: JobTrigger<'a>,
         timeout: &'a str,
+        options: JobOptions,
     },
     Method {
         name: &'a str,
-        job: fn(arg: Arc<Box<dyn Any + Send + Sync>>),
-        cron_expr: String,
+        job: MethodFn,
+        trigger: JobTrigger<'a>,
         timeout: String,
         instance: Arc<Box<dyn Any + Send + Sync>>,
+        options: JobOptions,
     },
     Function {
         name: &'a str,
-        job: fn(),
-        cron_expr: &'a str,
+        job: JobFn,
+        trigger: JobTrigger<'a>,
         timeout: &'a str,
+        options: JobOptions,
     },
     CLI {
         name: &'a str,
         cron_expr: &'a str,
         timeout: &'a str,
+        options: JobOptions,
     },
 }
 
@@ -42,9 +322,42 @@ impl<'a> JobBuilder<'a> {
     ) -> Self {
         JobBuilder::Global {
             name,
-            job,
-            cron_expr,
+            job: JobFn::Plain(job),
+            trigger: JobTrigger::expr(cron_expr),
+            timeout,
+            options: JobOptions::new(),
+        }
+    }
+
+    // a global job whose function takes the context of the run
+    pub const fn global_job_with_context(
+        name: &'a str,
+        job: fn(&JobContext),
+        cron_expr: &'a str,
+        timeout: &'a str,
+    ) -> Self {
+        JobBuilder::Global {
+            name,
+            job: JobFn::Context(job),
+            trigger: JobTrigger::expr(cron_expr),
+            timeout,
+            options: JobOptions::new(),
+        }
+    }
+
+    // a global job with a typed trigger instead of an expression
+    pub const fn global_job_with_trigger(
+        name: &'a str,
+        job: fn(),
+        trigger: Trigger,
+        timeout: &'a str,
+    ) -> Self {
+        JobBuilder::Global {
+            name,
+            job: JobFn::Plain(job),
+            trigger: JobTrigger::Trigger(trigger),
             timeout,
+            options: JobOptions::new(),
         }
     }
 
@@ -57,10 +370,30 @@ impl<'a> JobBuilder<'a> {
     ) -> Self {
         JobBuilder::Method {
             name,
-            job,
-            cron_expr,
+            job: MethodFn::Plain(job),
+            trigger: JobTrigger::Expr(Cow::Owned(cron_expr)),
+            timeout,
+            instance,
+            options: JobOptions::new(),
+        }
+    }
+
+    // a method job whose function takes the context of the run
+    #[allow(clippy::type_complexity)]
+    pub const fn method_job_with_context(
+        name: &'a str,
+        job: fn(Arc<Box<dyn Any + Send + Sync>>, &JobContext),
+        cron_expr: String,
+        timeout: String,
+        instance: Arc<Box<dyn Any + Send + Sync>>,
+    ) -> Self {
+        JobBuilder::Method {
+            name,
+            job: MethodFn::Context(job),
+            trigger: JobTrigger::Expr(Cow::Owned(cron_expr)),
             timeout,
             instance,
+            options: JobOptions::new(),
         }
     }
 
@@ -72,9 +405,42 @@ impl<'a> JobBuilder<'a> {
     ) -> Self {
         JobBuilder::Function {
             name,
-            job,
-            cron_expr,
+            job: JobFn::Plain(job),
+            trigger: JobTrigger::expr(cron_expr),
+            timeout,
+            options: JobOptions::new(),
+        }
+    }
+
+    // a function job whose function takes the context of the run
+    pub const fn function_job_with_context(
+        name: &'a str,
+        job: fn(&JobContext),
+        cron_expr: &'a str,
+        timeout: &'a str,
+    ) -> Self {
+        JobBuilder::Function {
+            name,
+            job: JobFn::Context(job),
+            trigger: JobTrigger::expr(cron_expr),
+            timeout,
+            options: JobOptions::new(),
+        }
+    }
+
+    // a function job with a typed trigger instead of an expression
+    pub const fn function_job_with_trigger(
+        name: &'a str,
+        job: fn(),
+        trigger: Trigger,
+        timeout: &'a str,
+    ) -> Self {
+        JobBuilder::Function {
+            name,
+            job: JobFn::Plain(job),
+            trigger: JobTrigger::Trigger(trigger),
             timeout,
+            options: JobOptions::new(),
         }
     }
 
@@ -83,80 +449,75 @@ impl<'a> JobBuilder<'a> {
             name,
             cron_expr,
             timeout,
+            options: JobOptions::new(),
         }
     }
 
+    // it replaces the scheduling options of the job to build
+    pub fn with_options(mut self, new_options: JobOptions) -> Self {
+        match &mut self {
+            Self::Global { options, .. }
+            | Self::Method { options, .. }
+            | Self::Function { options, .. }
+            | Self::CLI { options, .. } => *options = new_options,
+        }
+        self
+    }
+
     // it matches on the job variant to build and builds it
-    pub fn build(self) -> CronJob {
-        match self {
+    // an error is returned if the name, the expression or the timeout of the job is invalid
+    pub fn build(self) -> Result<CronJob, CronFrameError> {
+        let cron_job = match self {
             Self::Global {
                 name,
                 job,
-                cron_expr,
+                trigger,
                 timeout,
+                options,
             } => {
-                let schedule =
-                    Schedule::from_str(cron_expr).expect("Failed to parse cron expression!");
-                let timeout: i64 = timeout.parse().expect("Failed to parse timeout!");
-                let timeout = if timeout > 0 {
-                    Some(Duration::milliseconds(timeout))
-                } else {
-                    None
-                };
-                CronJob::new_global(name, job, schedule, timeout)
+                let (trigger, timeout) = parse_definition(name, trigger, timeout)?;
+                let mut cron_job = CronJob::new_global(name, job, trigger, timeout);
+                options.apply(&mut cron_job);
+                cron_job
             }
             Self::Method {
                 name,
                 job,
-                cron_expr,
+                trigger,
                 timeout,
                 instance,
+                options,
             } => {
-                let schedule =
-                    Schedule::from_str(&cron_expr).expect("Failed to parse cron expression!");
-                let timeout: i64 = timeout.parse().expect("Failed to parse timeout!");
-                let timeout = if timeout > 0 {
-                    Some(Duration::milliseconds(timeout))
-                } else {
-                    None
-                };
-
-                CronJob::new_method(name, instance, job, schedule, timeout)
+                let (trigger, timeout) = parse_definition(name, trigger, &timeout)?;
+                let mut cron_job = CronJob::new_method(name, instance, job, trigger, timeout);
+                options.apply(&mut cron_job);
+                cron_job
             }
             Self::Function {
                 name,
                 job,
-                cron_expr,
+                trigger,
                 timeout,
+                options,
             } => {
-                let schedule =
-                    Schedule::from_str(cron_expr).expect("Failed to parse cron expression!");
-                let timeout: i64 = timeout.parse().expect("Failed to parse timeout!");
-                let timeout = if timeout > 0 {
-                    Some(Duration::milliseconds(timeout))
-                } else {
-                    None
-                };
-
-                CronJob::new_function(name, job, schedule, timeout)
+                let (trigger, timeout) = parse_definition(name, trigger, timeout)?;
+                let mut cron_job = CronJob::new_function(name, job, trigger, timeout);
+                options.apply(&mut cron_job);
+                cron_job
             }
             Self::CLI {
                 name,
                 cron_expr,
                 timeout,
+                options,
             } => {
                 let cron_expr = cron_expr.replace("slh", "/").replace("%20", " ");
-                let schedule =
-                    Schedule::from_str(&cron_expr).expect("Failed to parse cron expression!");
-                let timeout: i64 = timeout.parse().expect("Failed to parse timeout!");
-                let timeout = if timeout > 0 {
-                    Some(Duration::milliseconds(timeout))
-                } else {
-                    None
-                };
-
-                CronJob::new_cli(name, schedule, timeout)
+                let (trigger, timeout) = parse_definition(name, cron_expr.into(), timeout)?;
+                let mut cron_job = CronJob::new_cli(name, trigger, timeout);
+                options.apply(&mut cron_job);
+                cron_job
             }
-        }
+        };
+        Ok(cron_job)
     }
 }
@@ -0,0 +1,80 @@
+use crate::{cancellation::CancellationToken, history::RunOrigin};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+/// Context handed to the jobs taking a `&JobContext` argument
+///
+/// It carries the metadata of the run, such as the run id also found in the log,
+/// and its cancellation token.
+///
+/// ```
+/// use cronframe::{JobBuilder, JobContext};
+///
+/// fn my_job(ctx: &JobContext) {
+///     println!("{} run_uuid#{} - attempt {}", ctx.job_name(), ctx.run_id(), ctx.attempt());
+///
+///     while !ctx.is_cancelled() {
+///         // do some work
+///         # break;
+///     }
+/// }
+///
+/// let job = JobBuilder::global_job_with_context("my_job", my_job, "0 0 3 * * * *", "0")
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct JobContext {
+    pub(crate) job_name: String,
+    pub(crate) job_id: Uuid,
+    pub(crate) run_id: Uuid,
+    pub(crate) fire_time: DateTime<Utc>,
+    pub(crate) start_time: DateTime<Utc>,
+    pub(crate) attempt: u32,
+    pub(crate) origin: RunOrigin,
+    pub(crate) token: CancellationToken,
+}
+
+impl JobContext {
+    pub fn job_name(&self) -> &str {
+        &self.job_name
+    }
+
+    pub fn job_id(&self) -> Uuid {
+        self.job_id
+    }
+
+    // the id of the run, the same logged by the scheduler
+    pub fn run_id(&self) -> Uuid {
+        self.run_id
+    }
+
+    // the time the run was scheduled for
+    pub fn fire_time(&self) -> DateTime<Utc> {
+        self.fire_time
+    }
+
+    // the time the run actually started, it differs from the fire time when waiting for a worker or another run
+    pub fn start_time(&self) -> DateTime<Utc> {
+        self.start_time
+    }
+
+    // the attempt number of the run, starting at 1
+    pub fn attempt(&self) -> u32 {
+        self.attempt
+    }
+
+    // whether the run was due to the trigger of the job or requested manually
+    pub fn origin(&self) -> RunOrigin {
+        self.origin
+    }
+
+    // the token of the run, cancelled on quit, scheduler stop, suspension of the job or max runtime exceeded
+    pub fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+}
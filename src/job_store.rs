@@ -0,0 +1,702 @@
+use crate::{
+    cronjob::CronJob,
+    history::{RunOrigin, RunOutcome, RunRecord},
+    job_builder::JobBuilder,
+};
+use chrono::{DateTime, Utc};
+use crossbeam_channel::Sender;
+use rocket::serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Mutex,
+};
+use uuid::Uuid;
+
+/// Storage for the state of the jobs, so that it survives a restart of the framework
+///
+/// Jobs are identified by their id, the state of every job is saved
+/// whenever it changes and it is reloaded when the framework is initialised.
+/// A job added after a restart takes the id of the first stored job with the same type and name
+/// that has not been restored yet, so the jobs of two instances of a cron object keep their own state.
+/// The framework writes to the store from a thread of its own, in the order the changes happen.
+/// CLI jobs are recreated from their stored definition, for the other jobs the
+/// overrides made at runtime, the suspension and the run history are restored.
+///
+/// ```no_run
+/// use cronframe::{ConfigData, CronFrame, FileStore};
+///
+/// let store = FileStore::new("jobs.json").unwrap();
+/// let cronframe = CronFrame::with_store(ConfigData::default(), store).unwrap();
+/// ```
+pub trait JobStore: Send + Sync {
+    /// every job kept by the store, in the order they were first saved
+    fn load(&self) -> Result<Vec<StoredJob>, String>;
+    /// it inserts or replaces the job with the same id, along with its history
+    fn save(&self, job: &StoredJob) -> Result<(), String>;
+    /// it inserts or replaces the job with the same id, leaving its history as it is
+    fn save_state(&self, job: &StoredJob) -> Result<(), String>;
+    /// it adds a run to the history of a job, only the latest `history_size` runs are kept
+    fn append_run(&self, job_id: &str, run: &StoredRun, history_size: usize) -> Result<(), String>;
+    fn remove(&self, job_id: &str) -> Result<(), String>;
+}
+
+/// Values changed at runtime through the web server, they take precedence over the job definition
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "rocket::serde")]
+pub struct JobOverrides {
+    pub cron_expr: Option<String>,
+    /// in ms, 0 for no timeout
+    pub timeout: Option<i64>,
+    pub retries: Option<u32>,
+}
+
+/// State of a job as kept by a `JobStore`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "rocket::serde")]
+pub struct StoredJob {
+    pub id: String,
+    pub name: String,
+    pub job_type: String,
+    pub cron_expr: String,
+    /// in ms, 0 for no timeout
+    pub timeout: i64,
+    pub suspended: bool,
+    #[serde(default)]
+    pub overrides: JobOverrides,
+    #[serde(default)]
+    pub history: Vec<StoredRun>,
+    /// the latest fire time in RFC 3339 format, missed runs are caught up from it
+    #[serde(default)]
+    pub last_fire: Option<String>,
+}
+
+/// A `RunRecord` as kept by a `JobStore`, times are in RFC 3339 format
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "rocket::serde")]
+pub struct StoredRun {
+    pub run_id: String,
+    pub attempt: u32,
+    pub fire_time: String,
+    pub start_time: String,
+    pub end_time: String,
+    pub outcome: RunOutcome,
+    #[serde(default)]
+    pub origin: RunOrigin,
+    pub error: Option<String>,
+}
+
+impl From<&RunRecord> for StoredRun {
+    fn from(record: &RunRecord) -> Self {
+        StoredRun {
+            run_id: record.run_id.to_string(),
+            attempt: record.attempt,
+            fire_time: record.fire_time.to_rfc3339(),
+            start_time: record.start_time.to_rfc3339(),
+            end_time: record.end_time.to_rfc3339(),
+            outcome: record.outcome,
+            origin: record.origin,
+            error: record.error.clone(),
+        }
+    }
+}
+
+impl StoredRun {
+    // None if the stored values cannot be parsed
+    fn to_record(&self) -> Option<RunRecord> {
+        let time = |value: &str| {
+            DateTime::parse_from_rfc3339(value)
+                .ok()
+                .map(|time| time.with_timezone(&Utc))
+        };
+
+        Some(RunRecord {
+            run_id: Uuid::from_str(&self.run_id).ok()?,
+            attempt: self.attempt,
+            fire_time: time(&self.fire_time)?,
+            start_time: time(&self.start_time)?,
+            end_time: time(&self.end_time)?,
+            outcome: self.outcome,
+            origin: self.origin,
+            error: self.error.clone(),
+        })
+    }
+}
+
+impl StoredJob {
+    pub(crate) fn from_job(cron_job: &CronJob) -> StoredJob {
+        StoredJob {
+            history: cron_job.history().iter().map(StoredRun::from).collect(),
+            ..StoredJob::state_of(cron_job)
+        }
+    }
+
+    // the state of the job without its run history
+    pub(crate) fn state_of(cron_job: &CronJob) -> StoredJob {
+        StoredJob {
+            id: cron_job.id().to_string(),
+            name: cron_job.name(),
+            job_type: cron_job.type_to_string(),
+            cron_expr: cron_job.schedule(),
+            timeout: cron_job
+                .timeout()
+                .map_or(0, |timeout| timeout.num_milliseconds()),
+            suspended: cron_job.suspended(),
+            overrides: cron_job.overrides().clone(),
+            history: Vec::new(),
+            last_fire: cron_job
+                .last_fire()
+                .or(cron_job.catch_up_from())
+                .map(|time| time.to_rfc3339()),
+        }
+    }
+
+    // a job with the same type and name, the jobs of two instances of a cron object are told apart
+    // by the order they are added in, each stored job being applied to a single job
+    pub(crate) fn matches(&self, cron_job: &CronJob) -> bool {
+        self.job_type == cron_job.type_to_string() && self.name == cron_job.name()
+    }
+
+    // it restores the id, the overrides, the suspension, the run history and the last fire time of the job
+    pub(crate) fn apply(&self, cron_job: &mut CronJob, history_size: usize) {
+        if let Ok(id) = Uuid::from_str(&self.id) {
+            cron_job.set_id(id);
+        }
+        if let Some(cron_expr) = &self.overrides.cron_expr {
+            cron_job.set_schedule(cron_expr);
+        }
+        if let Some(timeout) = self.overrides.timeout {
+            cron_job.set_timeout(timeout);
+        }
+        if let Some(retries) = self.overrides.retries {
+            cron_job.set_retries(retries);
+        }
+        if self.suspended {
+            cron_job.suspension(true);
+        }
+
+        let records = self.history.iter().filter_map(StoredRun::to_record);
+        cron_job.restore_history(records, history_size);
+
+        let last_fire = self
+            .last_fire
+            .as_deref()
+            .and_then(|value| DateTime::parse_from_rfc3339(value).ok());
+        if let Some(last_fire) = last_fire {
+            cron_job.set_catch_up_from(last_fire.with_timezone(&Utc));
+        }
+    }
+
+    // it recreates a CLI job from its definition, None for the other kinds of jobs
+    pub(crate) fn to_cli_job(&self, history_size: usize) -> Option<CronJob> {
+        if self.job_type != "CLI" {
+            return None;
+        }
+
+        let mut cron_job =
+            JobBuilder::cli_job(&self.name, &self.cron_expr, &self.timeout.to_string())
+                .build()
+                .ok()?;
+        self.apply(&mut cron_job, history_size);
+        Some(cron_job)
+    }
+}
+
+// a change to the job store, carried out by the store writer
+pub(crate) enum StoreWrite {
+    Save(StoredJob),
+    SaveState(StoredJob),
+    // the name of the job is only used to log an error
+    AppendRun {
+        job_id: String,
+        name: String,
+        run: StoredRun,
+        history_size: usize,
+    },
+    Remove {
+        job_id: String,
+        name: String,
+    },
+    // answered once the writes sent before it are done
+    Flush(Sender<()>),
+}
+
+// it writes to the store on a thread of its own so that the scheduler is not held up by the disk
+// the writes are carried out in the order they are sent, the thread ends with the writer
+pub(crate) struct StoreWriter {
+    tx: Sender<StoreWrite>,
+}
+
+impl StoreWriter {
+    pub fn spawn(store: Box<dyn JobStore>) -> StoreWriter {
+        let (tx, rx) = crossbeam_channel::unbounded::<StoreWrite>();
+
+        std::thread::spawn(move || {
+            for write in rx {
+                let (name, result) = match write {
+                    StoreWrite::Save(job) => (job.name.clone(), store.save(&job)),
+                    StoreWrite::SaveState(job) => (job.name.clone(), store.save_state(&job)),
+                    StoreWrite::AppendRun {
+                        job_id,
+                        name,
+                        run,
+                        history_size,
+                    } => (name, store.append_run(&job_id, &run, history_size)),
+                    StoreWrite::Remove { job_id, name } => (name, store.remove(&job_id)),
+                    StoreWrite::Flush(done_tx) => {
+                        let _ = done_tx.send(());
+                        continue;
+                    }
+                };
+
+                if let Err(error) = result {
+                    error!("job name@{name} - Store Error - {error}");
+                }
+            }
+        });
+
+        StoreWriter { tx }
+    }
+
+    pub fn send(&self, write: StoreWrite) {
+        let _ = self.tx.send(write);
+    }
+
+    // it waits for the writes sent so far
+    pub fn flush(&self) {
+        let (done_tx, done_rx) = crossbeam_channel::bounded(1);
+        self.send(StoreWrite::Flush(done_tx));
+        let _ = done_rx.recv();
+    }
+}
+
+// it opens the store at the given path, SQLite for .db, .sqlite and .sqlite3 files
+// TOML for .toml files and JSON otherwise
+pub(crate) fn open_store(path: &str) -> Result<Box<dyn JobStore>, String> {
+    let extension = Path::new(path)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default();
+
+    match extension {
+        "db" | "sqlite" | "sqlite3" => {
+            #[cfg(feature = "sqlite")]
+            return Ok(Box::new(SqliteStore::open(path)?));
+            #[cfg(not(feature = "sqlite"))]
+            return Err(format!(
+                "store \"{path}\" requires the sqlite feature of cronframe"
+            ));
+        }
+        _ => Ok(Box::new(FileStore::new(path)?)),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FileFormat {
+    Json,
+    Toml,
+}
+
+// layout of a store file, a table is required at the root of a TOML document
+#[derive(Serialize, Deserialize, Default)]
+#[serde(crate = "rocket::serde")]
+struct StoreFile {
+    #[serde(default)]
+    jobs: Vec<StoredJob>,
+}
+
+/// `JobStore` keeping every job in a single JSON or TOML file, chosen by the file extension
+///
+/// The whole file is rewritten at every write, `SqliteStore` suits many jobs with a long history better.
+pub struct FileStore {
+    path: PathBuf,
+    format: FileFormat,
+    jobs: Mutex<Vec<StoredJob>>,
+}
+
+impl FileStore {
+    /// It opens the store, the file is created at the first save if it does not exist
+    pub fn new(path: impl AsRef<Path>) -> Result<FileStore, String> {
+        let path = path.as_ref().to_path_buf();
+        let format = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => FileFormat::Toml,
+            _ => FileFormat::Json,
+        };
+
+        let jobs = match fs::read_to_string(&path) {
+            Ok(content) if content.trim().is_empty() => Vec::new(),
+            Ok(content) => {
+                let file: StoreFile = match format {
+                    FileFormat::Json => serde_json::from_str(&content).map_err(|error| {
+                        format!("store file {} parse error: {error}", path.display())
+                    })?,
+                    FileFormat::Toml => toml::from_str(&content).map_err(|error| {
+                        format!("store file {} parse error: {error}", path.display())
+                    })?,
+                };
+                file.jobs
+            }
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(error) => return Err(format!("store file {} error: {error}", path.display())),
+        };
+
+        Ok(FileStore {
+            path,
+            format,
+            jobs: Mutex::new(jobs),
+        })
+    }
+
+    // the file is written next to its final location and renamed, so that a crash does not corrupt it
+    fn write(&self, jobs: &[StoredJob]) -> Result<(), String> {
+        let file = StoreFile {
+            jobs: jobs.to_vec(),
+        };
+        let content = match self.format {
+            FileFormat::Json => {
+                serde_json::to_string_pretty(&file).map_err(|error| error.to_string())?
+            }
+            FileFormat::Toml => toml::to_string(&file).map_err(|error| error.to_string())?,
+        };
+
+        let tmp_path = self.path.with_extension("tmp");
+        fs::write(&tmp_path, content)
+            .and_then(|_| fs::rename(&tmp_path, &self.path))
+            .map_err(|error| format!("store file {} error: {error}", self.path.display()))
+    }
+}
+
+impl JobStore for FileStore {
+    fn load(&self) -> Result<Vec<StoredJob>, String> {
+        Ok(self
+            .jobs
+            .lock()
+            .expect("store jobs unwrap error in load")
+            .clone())
+    }
+
+    fn save(&self, job: &StoredJob) -> Result<(), String> {
+        let mut jobs = self.jobs.lock().expect("store jobs unwrap error in save");
+        match jobs.iter_mut().find(|stored| stored.id == job.id) {
+            Some(stored) => *stored = job.clone(),
+            None => jobs.push(job.clone()),
+        }
+        self.write(&jobs)
+    }
+
+    fn save_state(&self, job: &StoredJob) -> Result<(), String> {
+        let mut jobs = self
+            .jobs
+            .lock()
+            .expect("store jobs unwrap error in save_state");
+        match jobs.iter_mut().find(|stored| stored.id == job.id) {
+            Some(stored) => {
+                let history = std::mem::take(&mut stored.history);
+                *stored = StoredJob {
+                    history,
+                    ..job.clone()
+                };
+            }
+            None => jobs.push(job.clone()),
+        }
+        self.write(&jobs)
+    }
+
+    fn append_run(&self, job_id: &str, run: &StoredRun, history_size: usize) -> Result<(), String> {
+        let mut jobs = self
+            .jobs
+            .lock()
+            .expect("store jobs unwrap error in append_run");
+        let Some(stored) = jobs.iter_mut().find(|stored| stored.id == job_id) else {
+            return Ok(());
+        };
+
+        stored.history.push(run.clone());
+        let excess = stored.history.len().saturating_sub(history_size);
+        stored.history.drain(..excess);
+        self.write(&jobs)
+    }
+
+    fn remove(&self, job_id: &str) -> Result<(), String> {
+        let mut jobs = self.jobs.lock().expect("store jobs unwrap error in remove");
+        jobs.retain(|stored| stored.id != job_id);
+        self.write(&jobs)
+    }
+}
+
+// outcomes and origins are stored by the name of their variant
+#[cfg(feature = "sqlite")]
+fn outcome_from_name(name: &str) -> RunOutcome {
+    match name {
+        "Completed" => RunOutcome::Completed,
+        "Failed" => RunOutcome::Failed,
+        "TimedOut" => RunOutcome::TimedOut,
+        _ => RunOutcome::Aborted,
+    }
+}
+
+#[cfg(feature = "sqlite")]
+fn origin_from_name(name: &str) -> RunOrigin {
+    match name {
+        "Manual" => RunOrigin::Manual,
+        _ => RunOrigin::Scheduled,
+    }
+}
+
+// it inserts the job or updates the one with the same id, its runs are left as they are
+#[cfg(feature = "sqlite")]
+fn upsert_job(connection: &rusqlite::Connection, job: &StoredJob) -> Result<(), String> {
+    connection
+        .execute(
+            "INSERT INTO jobs VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+            ON CONFLICT (id) DO UPDATE SET
+                cron_expr = excluded.cron_expr,
+                timeout = excluded.timeout,
+                suspended = excluded.suspended,
+                override_cron_expr = excluded.override_cron_expr,
+                override_timeout = excluded.override_timeout,
+                override_retries = excluded.override_retries,
+                last_fire = excluded.last_fire",
+            rusqlite::params![
+                job.id,
+                job.job_type,
+                job.name,
+                job.cron_expr,
+                job.timeout,
+                job.suspended,
+                job.overrides.cron_expr,
+                job.overrides.timeout,
+                job.overrides.retries,
+                job.last_fire,
+            ],
+        )
+        .map(|_| ())
+        .map_err(|error| error.to_string())
+}
+
+#[cfg(feature = "sqlite")]
+fn insert_run(
+    connection: &rusqlite::Connection,
+    job_id: &str,
+    position: i64,
+    run: &StoredRun,
+) -> Result<(), String> {
+    let outcome = format!("{:?}", run.outcome);
+    let origin = format!("{:?}", run.origin);
+    connection
+        .execute(
+            "INSERT INTO runs VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            rusqlite::params![
+                job_id,
+                position,
+                run.run_id,
+                run.attempt,
+                run.fire_time,
+                run.start_time,
+                run.end_time,
+                outcome,
+                run.error,
+                origin,
+            ],
+        )
+        .map(|_| ())
+        .map_err(|error| error.to_string())
+}
+
+/// `JobStore` backed by an embedded SQLite database, it requires the `sqlite` feature
+#[cfg(feature = "sqlite")]
+pub struct SqliteStore {
+    connection: Mutex<rusqlite::Connection>,
+}
+
+#[cfg(feature = "sqlite")]
+impl SqliteStore {
+    /// It opens the database, creating it along with its tables if need be
+    pub fn open(path: impl AsRef<Path>) -> Result<SqliteStore, String> {
+        let connection = rusqlite::Connection::open(path).map_err(|error| error.to_string())?;
+
+        connection
+            .execute_batch(
+                "CREATE TABLE IF NOT EXISTS jobs (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    job_type TEXT NOT NULL,
+                    name TEXT NOT NULL,
+                    cron_expr TEXT NOT NULL,
+                    timeout INTEGER NOT NULL,
+                    suspended INTEGER NOT NULL,
+                    override_cron_expr TEXT,
+                    override_timeout INTEGER,
+                    override_retries INTEGER,
+                    last_fire TEXT
+                );
+                CREATE TABLE IF NOT EXISTS runs (
+                    job_id TEXT NOT NULL,
+                    position INTEGER NOT NULL,
+                    run_id TEXT NOT NULL,
+                    attempt INTEGER NOT NULL,
+                    fire_time TEXT NOT NULL,
+                    start_time TEXT NOT NULL,
+                    end_time TEXT NOT NULL,
+                    outcome TEXT NOT NULL,
+                    error TEXT,
+                    origin TEXT NOT NULL DEFAULT 'Scheduled'
+                );",
+            )
+            .map_err(|error| error.to_string())?;
+
+        Ok(SqliteStore {
+            connection: Mutex::new(connection),
+        })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl JobStore for SqliteStore {
+    fn load(&self) -> Result<Vec<StoredJob>, String> {
+        let connection = self
+            .connection
+            .lock()
+            .expect("store connection unwrap error in load");
+
+        let mut jobs_query = connection
+            .prepare(
+                "SELECT job_type, name, id, cron_expr, timeout, suspended,
+                override_cron_expr, override_timeout, override_retries, last_fire
+                FROM jobs ORDER BY rowid",
+            )
+            .map_err(|error| error.to_string())?;
+        let mut runs_query = connection
+            .prepare(
+                "SELECT run_id, attempt, fire_time, start_time, end_time, outcome, error, origin
+                FROM runs WHERE job_id = ?1 ORDER BY position",
+            )
+            .map_err(|error| error.to_string())?;
+
+        let jobs = jobs_query
+            .query_map([], |row| {
+                Ok(StoredJob {
+                    job_type: row.get(0)?,
+                    name: row.get(1)?,
+                    id: row.get(2)?,
+                    cron_expr: row.get(3)?,
+                    timeout: row.get(4)?,
+                    suspended: row.get(5)?,
+                    overrides: JobOverrides {
+                        cron_expr: row.get(6)?,
+                        timeout: row.get(7)?,
+                        retries: row.get(8)?,
+                    },
+                    history: Vec::new(),
+                    last_fire: row.get(9)?,
+                })
+            })
+            .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+            .map_err(|error| error.to_string())?;
+
+        jobs.into_iter()
+            .map(|mut job| {
+                job.history = runs_query
+                    .query_map([&job.id], |row| {
+                        let outcome: String = row.get(5)?;
+                        let origin: String = row.get(7)?;
+                        Ok(StoredRun {
+                            run_id: row.get(0)?,
+                            attempt: row.get(1)?,
+                            fire_time: row.get(2)?,
+                            start_time: row.get(3)?,
+                            end_time: row.get(4)?,
+                            outcome: outcome_from_name(&outcome),
+                            origin: origin_from_name(&origin),
+                            error: row.get(6)?,
+                        })
+                    })
+                    .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+                    .map_err(|error| error.to_string())?;
+                Ok(job)
+            })
+            .collect()
+    }
+
+    fn save(&self, job: &StoredJob) -> Result<(), String> {
+        let mut connection = self
+            .connection
+            .lock()
+            .expect("store connection unwrap error in save");
+        let transaction = connection
+            .transaction()
+            .map_err(|error| error.to_string())?;
+
+        upsert_job(&transaction, job)?;
+        transaction
+            .execute("DELETE FROM runs WHERE job_id = ?1", [&job.id])
+            .map_err(|error| error.to_string())?;
+
+        for (position, run) in job.history.iter().enumerate() {
+            insert_run(&transaction, &job.id, position as i64, run)?;
+        }
+
+        transaction.commit().map_err(|error| error.to_string())
+    }
+
+    fn save_state(&self, job: &StoredJob) -> Result<(), String> {
+        let connection = self
+            .connection
+            .lock()
+            .expect("store connection unwrap error in save_state");
+        upsert_job(&connection, job)
+    }
+
+    fn append_run(&self, job_id: &str, run: &StoredRun, history_size: usize) -> Result<(), String> {
+        let mut connection = self
+            .connection
+            .lock()
+            .expect("store connection unwrap error in append_run");
+        let transaction = connection
+            .transaction()
+            .map_err(|error| error.to_string())?;
+
+        let stored: bool = transaction
+            .query_row(
+                "SELECT EXISTS (SELECT 1 FROM jobs WHERE id = ?1)",
+                [job_id],
+                |row| row.get(0),
+            )
+            .map_err(|error| error.to_string())?;
+        if !stored {
+            return Ok(());
+        }
+
+        let last: Option<i64> = transaction
+            .query_row(
+                "SELECT MAX(position) FROM runs WHERE job_id = ?1",
+                [job_id],
+                |row| row.get(0),
+            )
+            .map_err(|error| error.to_string())?;
+        let position = last.map_or(0, |last| last + 1);
+
+        insert_run(&transaction, job_id, position, run)?;
+        transaction
+            .execute(
+                "DELETE FROM runs WHERE job_id = ?1 AND position <= ?2",
+                rusqlite::params![job_id, position - history_size as i64],
+            )
+            .map_err(|error| error.to_string())?;
+
+        transaction.commit().map_err(|error| error.to_string())
+    }
+
+    fn remove(&self, job_id: &str) -> Result<(), String> {
+        let connection = self
+            .connection
+            .lock()
+            .expect("store connection unwrap error in remove");
+
+        connection
+            .execute("DELETE FROM jobs WHERE id = ?1", [job_id])
+            .and_then(|_| connection.execute("DELETE FROM runs WHERE job_id = ?1", [job_id]))
+            .map(|_| ())
+            .map_err(|error| error.to_string())
+    }
+}
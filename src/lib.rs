@@ -2,13 +2,14 @@
 #[macro_use]
 extern crate rocket;
 
+#[doc(hidden)]
+pub use chrono::Duration;
+pub use chrono_tz::Tz;
 pub use cronframe_macro::{cron, cron_impl, cron_obj, fn_job, mt_job};
 #[doc(hidden)]
 pub use crossbeam_channel::{bounded, unbounded, Receiver, Sender};
 #[doc(hidden)]
-pub use linkme::distributed_slice;
-#[doc(hidden)]
-pub use log::info;
+pub use log::{error, info};
 #[doc(hidden)]
 pub use once_cell::sync::Lazy;
 #[doc(hidden)]
@@ -20,21 +21,41 @@ pub use std::{
 };
 
 // cronframe modules
+mod cancellation;
 mod config;
+mod cron_object;
 mod cronframe;
 mod cronframe_expr;
 mod cronjob;
+mod history;
 mod job_builder;
+mod job_context;
+mod job_store;
 pub mod logger;
+mod trigger;
 pub mod utils;
 mod web_server;
+mod worker_pool;
 
 // re-exports
+pub use cancellation::CancellationToken;
 pub use config::{ConfigData, LoggerConfig, SchedulerConfig, ServerConfig};
+pub use cron_object::CronObject;
+#[doc(hidden)]
+pub use cron_object::{DropSignal, FunctionJobs};
 pub use cronframe::{CronFrame, SchedulerMessage};
 pub use cronframe_expr::CronFrameExpr;
-pub use cronjob::{CronFilter, CronJob};
-pub use job_builder::JobBuilder;
+pub use cronjob::{
+    Backoff, CatchUpPolicy, CronFilter, CronJob, JobFn, JobFuture, MethodFn, MisfirePolicy,
+    OverlapPolicy, RetryPolicy,
+};
+pub use history::{RunOrigin, RunOutcome, RunRecord};
+pub use job_builder::{CronFrameError, JobBuilder, JobOptions, JobTrigger};
+pub use job_context::JobContext;
+#[cfg(feature = "sqlite")]
+pub use job_store::SqliteStore;
+pub use job_store::{FileStore, JobOverrides, JobStore, StoredJob, StoredRun};
+pub use trigger::{parse_duration, Trigger};
 
 #[doc(hidden)]
 pub use inventory::{collect, submit};
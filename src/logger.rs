@@ -112,7 +112,7 @@ pub fn rolling_logger() -> log4rs::Handle {
         .encoder(Box::new(PatternEncoder::new(&pattern)))
         .append(false)
         .build(
-            &format!("{log_dir}/{latest_file_name}.log"),
+            format!("{log_dir}/{latest_file_name}.log"),
             Box::new(policy),
         )
         .expect("rolling_logger log file unwrap error");
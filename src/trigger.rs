@@ -0,0 +1,249 @@
+use chrono::{DateTime, Duration, LocalResult, Offset, SecondsFormat, TimeZone, Utc};
+use chrono_tz::Tz;
+use cron::{Schedule, TimeUnitSpec};
+use std::str::FromStr;
+
+/// What decides the fire times of a job
+///
+/// A trigger is built from its variants or parsed from a cron expression, from `every <duration>` for a fixed rate,
+/// from `delay <duration>` for a fixed delay after the completion of the previous run
+/// or from an RFC 3339 date time for a one-shot job that leaves the framework once it has fired.
+/// Durations take one of the `ms`, `s`, `m`, `h` and `d` units.
+///
+/// Cron expressions are evaluated on the wall clock of the time zone of the job.
+/// A fire time falling in a DST gap is shifted forward by the length of the gap,
+/// a fire time repeated by a DST overlap fires once, at its first occurrence,
+/// unless the hour field of the expression is a wildcard, then it fires at both.
+///
+/// ```
+/// use cronframe::{Duration, Trigger};
+///
+/// let trigger: Trigger = "2026-11-01T02:00:00Z".parse().unwrap();
+/// assert!(trigger.is_once());
+///
+/// let trigger: Trigger = "every 90s".parse().unwrap();
+/// assert_eq!(trigger, Trigger::Interval(Duration::seconds(90)));
+///
+/// let trigger: Trigger = "delay 10m".parse().unwrap();
+/// assert_eq!(trigger.to_string(), "delay 10m");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[allow(clippy::large_enum_variant)]
+pub enum Trigger {
+    /// the fire times of a cron schedule
+    Cron(Schedule),
+    /// fire times at a fixed rate, the first one an interval after the job is scheduled
+    Interval(Duration),
+    /// a fire time at a fixed delay after the runs of the previous one are over
+    Delay(Duration),
+    /// a single fire time
+    Once(DateTime<Utc>),
+}
+
+impl Trigger {
+    // it returns the first fire time strictly after the given time, cron schedules follow the given time zone
+    // for interval and delay triggers the given time is the latest fire or completion time
+    pub fn after(&self, time: &DateTime<Utc>, timezone: Tz) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Cron(schedule) if timezone == Tz::UTC => schedule.after(time).next(),
+            Self::Cron(schedule) => cron_after(schedule, time, timezone),
+            Self::Interval(every) | Self::Delay(every) => {
+                (*every > Duration::zero()).then(|| *time + *every)
+            }
+            Self::Once(at) => (at > time).then_some(*at),
+        }
+    }
+
+    // it returns the fire times after the first given time up to the second one included
+    // a delay trigger has no fire times of its own until the previous run is over
+    pub fn between(
+        &self,
+        from: &DateTime<Utc>,
+        to: &DateTime<Utc>,
+        timezone: Tz,
+    ) -> Vec<DateTime<Utc>> {
+        match self {
+            Self::Cron(schedule) if timezone == Tz::UTC => {
+                schedule.after(from).take_while(|time| time <= to).collect()
+            }
+            Self::Cron(schedule) => {
+                std::iter::successors(cron_after(schedule, from, timezone), |time| {
+                    cron_after(schedule, time, timezone)
+                })
+                .take_while(|time| time <= to)
+                .collect()
+            }
+            Self::Interval(every) if *every > Duration::zero() => {
+                std::iter::successors(Some(*from + *every), |time| Some(*time + *every))
+                    .take_while(|time| time <= to)
+                    .collect()
+            }
+            Self::Interval(_) | Self::Delay(_) => Vec::new(),
+            Self::Once(at) => (at > from && at <= to).then_some(*at).into_iter().collect(),
+        }
+    }
+
+    pub fn is_once(&self) -> bool {
+        matches!(self, Self::Once(_))
+    }
+
+    pub fn is_delay(&self) -> bool {
+        matches!(self, Self::Delay(_))
+    }
+}
+
+// a DST transition shifts the wall clock by a few hours at most
+const MAX_SHIFT_HOURS: i64 = 3;
+
+// true if the UTC offset of the zone changes within a few hours of the given time
+fn near_transition(time: &DateTime<Utc>, timezone: Tz) -> bool {
+    let offset = |time: DateTime<Utc>| {
+        timezone
+            .offset_from_utc_datetime(&time.naive_utc())
+            .fix()
+            .local_minus_utc()
+    };
+    let shift = Duration::hours(MAX_SHIFT_HOURS);
+    offset(*time - shift) != offset(*time) || offset(*time) != offset(*time + shift)
+}
+
+// the instants of a wall clock time of the zone, see the DST semantics of Trigger
+fn wall_instants(schedule: &Schedule, wall: &DateTime<Utc>, timezone: Tz) -> Vec<DateTime<Utc>> {
+    match timezone.from_local_datetime(&wall.naive_utc()) {
+        LocalResult::Single(time) => vec![time.with_timezone(&Utc)],
+        LocalResult::Ambiguous(earliest, latest) => {
+            if schedule.hours().is_all() {
+                vec![earliest.with_timezone(&Utc), latest.with_timezone(&Utc)]
+            } else {
+                vec![earliest.with_timezone(&Utc)]
+            }
+        }
+        // in a gap the offset in effect before it shifts the time forward by the length of the gap
+        LocalResult::None => {
+            let before = *wall - Duration::hours(MAX_SHIFT_HOURS);
+            match timezone.from_local_datetime(&before.naive_utc()).earliest() {
+                Some(before) => {
+                    let offset = before.offset().fix().local_minus_utc();
+                    vec![*wall - Duration::seconds(offset.into())]
+                }
+                None => Vec::new(),
+            }
+        }
+    }
+}
+
+// the first fire time of a cron schedule strictly after the given time, on the wall clock of the zone
+// wall clock times are handled as UTC ones to walk the schedule, then turned into instants of the zone
+fn cron_after(schedule: &Schedule, time: &DateTime<Utc>, timezone: Tz) -> Option<DateTime<Utc>> {
+    let margin = |time: &DateTime<Utc>| {
+        if near_transition(time, timezone) {
+            Duration::hours(MAX_SHIFT_HOURS)
+        } else {
+            Duration::zero()
+        }
+    };
+    let wall_clock =
+        |time: &DateTime<Utc>| Utc.from_utc_datetime(&time.with_timezone(&timezone).naive_local());
+
+    // an overlap repeats wall clock times already gone by
+    let start = wall_clock(time) - margin(time);
+    let mut found: Option<DateTime<Utc>> = None;
+
+    for wall in schedule.after(&start) {
+        if let Some(found) = &found {
+            if wall > wall_clock(found) + margin(found) {
+                break;
+            }
+        }
+        for instant in wall_instants(schedule, &wall, timezone) {
+            if instant > *time && found.is_none_or(|found| instant < found) {
+                found = Some(instant);
+            }
+        }
+    }
+    found
+}
+
+// durations are written with the largest unit that divides them, e.g. 90s or 10m
+fn duration_to_string(duration: &Duration) -> String {
+    let ms = duration.num_milliseconds();
+    let units = [
+        ("d", 86_400_000),
+        ("h", 3_600_000),
+        ("m", 60_000),
+        ("s", 1000),
+    ];
+    match units.iter().find(|(_, factor)| ms != 0 && ms % factor == 0) {
+        Some((unit, factor)) => format!("{}{unit}", ms / factor),
+        None => format!("{ms}ms"),
+    }
+}
+
+/// It parses a positive duration such as 500ms, 90s, 10m, 2h or 1d
+///
+/// None if the value is not valid or does not fit in a `Duration`.
+pub fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (amount, unit) = value.split_at(split);
+    let amount = amount.parse::<i64>().ok()?;
+
+    let duration = match unit.trim() {
+        "ms" => Duration::try_milliseconds(amount),
+        "s" => Duration::try_seconds(amount),
+        "m" => Duration::try_minutes(amount),
+        "h" => Duration::try_hours(amount),
+        "d" => Duration::try_days(amount),
+        _ => None,
+    }?;
+    (duration > Duration::zero()).then_some(duration)
+}
+
+impl From<Schedule> for Trigger {
+    fn from(schedule: Schedule) -> Self {
+        Self::Cron(schedule)
+    }
+}
+
+impl From<DateTime<Utc>> for Trigger {
+    fn from(at: DateTime<Utc>) -> Self {
+        Self::Once(at)
+    }
+}
+
+impl std::fmt::Display for Trigger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Cron(schedule) => write!(f, "{schedule}"),
+            Self::Interval(every) => write!(f, "every {}", duration_to_string(every)),
+            Self::Delay(delay) => write!(f, "delay {}", duration_to_string(delay)),
+            Self::Once(at) => write!(f, "{}", at.to_rfc3339_opts(SecondsFormat::Secs, true)),
+        }
+    }
+}
+
+impl FromStr for Trigger {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        if let Some(every) = value.strip_prefix("every ") {
+            return parse_duration(every)
+                .map(Self::Interval)
+                .ok_or_else(|| format!("invalid interval \"{every}\", expected e.g. \"90s\""));
+        }
+        if let Some(delay) = value.strip_prefix("delay ") {
+            return parse_duration(delay)
+                .map(Self::Delay)
+                .ok_or_else(|| format!("invalid delay \"{delay}\", expected e.g. \"10m\""));
+        }
+        if let Ok(at) = DateTime::parse_from_rfc3339(value) {
+            return Ok(Self::Once(at.with_timezone(&Utc)));
+        }
+        Schedule::from_str(value).map(Self::Cron).map_err(|error| {
+            format!("\"{value}\" is neither a cron expression nor an RFC 3339 date time: {error}")
+        })
+    }
+}
@@ -20,13 +20,13 @@ pub fn gen_template_dir() -> std::io::Result<()> {
     let templ_dir = if std::env::var("CRONFRAME_CLI").is_ok() {
         format!("{}/.cronframe/templates", home_dir())
     } else {
-        format!("./templates")
+        "./templates".to_string()
     };
 
     if !Path::new(&templ_dir).exists() {
         std::fs::create_dir(&templ_dir)?;
 
-        let files = vec![
+        let files = [
             ("base.html.tera", crate::web_server::BASE_TEMPLATE),
             ("index.html.tera", crate::web_server::INDEX_TEMPLATE),
             ("job.html.tera", crate::web_server::JOB_TEMPLATE),
@@ -37,8 +37,8 @@ pub fn gen_template_dir() -> std::io::Result<()> {
         ];
 
         for (file_name, content) in files {
-            let mut file = File::create(&format!("{templ_dir}/{file_name}"))?;
-            file.write(content.as_bytes())?;
+            let mut file = File::create(format!("{templ_dir}/{file_name}"))?;
+            file.write_all(content.as_bytes())?;
             file.sync_all()?;
         }
     }
@@ -12,6 +12,8 @@ use rocket::{
         providers::{Env, Format, Toml},
         Figment, Profile,
     },
+    http::Status,
+    response::status::BadRequest,
     serde::Serialize,
 };
 use rocket_dyn_templates::{context, Template};
@@ -75,8 +77,13 @@ pub fn web_server(frame: Arc<CronFrame>) -> Result<(), CFError> {
                 home,
                 job_info,
                 update_timeout,
+                update_retries,
                 update_schedule,
                 suspension_handle,
+                run_now,
+                run_job,
+                remove_job,
+                remove_jobs,
                 start_scheduler,
                 stop_scheduler,
                 add_cli_job,
@@ -116,7 +123,7 @@ async fn styles() -> Result<rocket::fs::NamedFile, std::io::Error> {
         let home_dir = utils::home_dir();
         rocket::fs::NamedFile::open(format!("{home_dir}/.cronframe/templates/styles.css")).await
     } else {
-        rocket::fs::NamedFile::open(format!("./templates/styles.css")).await
+        rocket::fs::NamedFile::open("./templates/styles.css").await
     }
 }
 
@@ -127,7 +134,7 @@ async fn tingle() -> Result<rocket::fs::NamedFile, std::io::Error> {
         let home_dir = utils::home_dir();
         rocket::fs::NamedFile::open(format!("{home_dir}/.cronframe/templates/tingle.css")).await
     } else {
-        rocket::fs::NamedFile::open(format!("./templates/tingle.css")).await
+        rocket::fs::NamedFile::open("./templates/tingle.css").await
     }
 }
 
@@ -138,7 +145,7 @@ async fn tinglejs() -> Result<rocket::fs::NamedFile, std::io::Error> {
         let home_dir = utils::home_dir();
         rocket::fs::NamedFile::open(format!("{home_dir}/.cronframe/templates/tingle.js")).await
     } else {
-        rocket::fs::NamedFile::open(format!("./templates/tingle.js")).await
+        rocket::fs::NamedFile::open("./templates/tingle.js").await
     }
 }
 
@@ -149,7 +156,7 @@ async fn cronframe() -> Result<rocket::fs::NamedFile, std::io::Error> {
         let home_dir = utils::home_dir();
         rocket::fs::NamedFile::open(format!("{home_dir}/.cronframe/templates/cronframe.js")).await
     } else {
-        rocket::fs::NamedFile::open(format!("./templates/cronframe.js")).await
+        rocket::fs::NamedFile::open("./templates/cronframe.js").await
     }
 }
 
@@ -160,6 +167,14 @@ struct JobList {
     id: String,
 }
 
+#[derive(Serialize)]
+#[serde(crate = "rocket::serde")]
+struct WorkersInfo {
+    size: usize,
+    busy: usize,
+    queued: usize,
+}
+
 // homepage returning a list of al jobs in the following categories: active, timed out, suspended
 #[get("/")]
 fn home(cronframe: &rocket::State<Arc<CronFrame>>) -> Template {
@@ -196,9 +211,15 @@ fn home(cronframe: &rocket::State<Arc<CronFrame>>) -> Template {
         }
     }
 
+    let workers = WorkersInfo {
+        size: cronframe.workers(),
+        busy: cronframe.busy_workers(),
+        queued: cronframe.queue_depth(),
+    };
+
     Template::render(
         "index",
-        context! {running, active_jobs, timedout_jobs, suspended_jobs},
+        context! {running, active_jobs, timedout_jobs, suspended_jobs, workers},
     )
 }
 
@@ -214,7 +235,31 @@ struct JobInfo {
     schedule: String,
     upcoming_utc: String,
     upcoming_local: String,
+    timezone: String,
+    upcoming_zone: String,
+    tags: String,
     fail: bool,
+    last_error: String,
+    overlap: String,
+    instances: usize,
+    max_runtime: String,
+    retry: String,
+    catch_up: String,
+    history: Vec<RunInfo>,
+}
+
+#[derive(Serialize, Default)]
+#[serde(crate = "rocket::serde")]
+struct RunInfo {
+    run_id: String,
+    attempt: u32,
+    fire_time: String,
+    start_time: String,
+    end_time: String,
+    duration: String,
+    outcome: String,
+    origin: String,
+    error: String,
 }
 
 // job page information where it is possilbe to change, schedule, timeout and toggle scheduling suspension
@@ -247,7 +292,48 @@ fn job_info(name: &str, id: &str, cronframe: &rocket::State<Arc<CronFrame>>) ->
                         "None".to_string()
                     }
                 },
+                timezone: job.timezone().unwrap_or_default().to_string(),
+                upcoming_zone: {
+                    if let Some(datetime) = job.upcoming_zone() {
+                        datetime.to_string()
+                    } else {
+                        "None".to_string()
+                    }
+                },
+                tags: job.tags().join(", "),
                 fail: job.failed(),
+                last_error: job.last_error().unwrap_or_default().to_string(),
+                overlap: job.overlap_policy().to_string(),
+                instances: job.run_ids().len(),
+                max_runtime: match job.max_runtime() {
+                    Some(max_runtime) => format!("{} ms", max_runtime.num_milliseconds()),
+                    None => "None".to_string(),
+                },
+                retry: job.retry_policy().to_string(),
+                catch_up: match job.catch_up_policy() {
+                    Some(policy) => policy.to_string(),
+                    None => format!(
+                        "{} (scheduler default)",
+                        cronframe.config().scheduler.catch_up
+                    ),
+                },
+                // latest runs first
+                history: job
+                    .history()
+                    .iter()
+                    .rev()
+                    .map(|record| RunInfo {
+                        run_id: record.run_id.to_string(),
+                        attempt: record.attempt,
+                        fire_time: record.fire_time.to_string(),
+                        start_time: record.start_time.to_string(),
+                        end_time: record.end_time.to_string(),
+                        duration: format!("{} ms", record.duration().num_milliseconds()),
+                        outcome: record.outcome.to_string(),
+                        origin: record.origin.to_string(),
+                        error: record.error.clone().unwrap_or_default(),
+                    })
+                    .collect(),
             };
             break;
         }
@@ -263,11 +349,25 @@ fn update_timeout(name: &str, id: &str, value: i64, cronframe: &rocket::State<Ar
         if job.name() == name && job.id().to_string() == id {
             let job_id = format!("{} ID#{}", job.name(), job.id());
             job.set_timeout(value);
+            cronframe.persist(job);
             info!("job @{job_id} - Timeout Update");
         }
     }
 }
 
+// API route to change the number of retries of a failed run
+#[get("/job/<name>/<id>/retryset/<value>")]
+fn update_retries(name: &str, id: &str, value: u32, cronframe: &rocket::State<Arc<CronFrame>>) {
+    for (_, job) in cronframe.jobs().lock().unwrap().iter_mut() {
+        if job.name() == name && job.id().to_string() == id {
+            let job_id = format!("{} ID#{}", job.name(), job.id());
+            job.set_retries(value);
+            cronframe.persist(job);
+            info!("job @{job_id} - Retries Update");
+        }
+    }
+}
+
 // API route to change the value of the cron expression and therefore the schedule
 #[get("/job/<name>/<id>/schedset/<expression>")]
 fn update_schedule(
@@ -279,6 +379,8 @@ fn update_schedule(
     for (job_id, job) in cronframe.jobs().lock().unwrap().iter_mut() {
         if job.name() == name && job.id().to_string() == id {
             if job.set_schedule(expression) {
+                cronframe.persist(job);
+                cronframe.reschedule(*job_id);
                 info!("job @{job_id} - Schedule Update");
             } else {
                 info!("job @{job_id} - Schedule Update Fail - Cron Expression Parse Error");
@@ -299,10 +401,84 @@ fn suspension_handle(name: &str, id: &str, cronframe: &rocket::State<Arc<CronFra
                 job.suspension(false);
                 info!("job @{job_id} - Scheduling Reprised");
             }
+            cronframe.persist(job);
         }
     }
 }
 
+// API route to run a job right away, outside of its schedule
+#[get("/job/<name>/<id>/run_now")]
+fn run_now(name: &str, id: &str, cronframe: &rocket::State<Arc<CronFrame>>) {
+    let job_id = cronframe
+        .jobs()
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|(_, job)| job.name() == name && job.id().to_string() == id)
+        .map(|(job_id, _)| *job_id);
+
+    if let Some(job_id) = job_id {
+        cronframe.trigger(job_id);
+    }
+}
+
+// API route to run the jobs with the given name right away, used by the cli tool
+// it responds with the ids of the runs, 404 if no job has that name and 409 if they are all suspended
+#[get("/run_job/<name>")]
+fn run_job(name: &str, cronframe: &rocket::State<Arc<CronFrame>>) -> Result<String, Status> {
+    let job_ids: Vec<_> = cronframe
+        .jobs()
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(_, job)| job.name() == name)
+        .map(|(job_id, _)| *job_id)
+        .collect();
+
+    if job_ids.is_empty() {
+        return Err(Status::NotFound);
+    }
+
+    let run_ids: Vec<_> = job_ids
+        .into_iter()
+        .filter_map(|job_id| cronframe.trigger(job_id))
+        .map(|run_id| run_id.to_string())
+        .collect();
+
+    if run_ids.is_empty() {
+        Err(Status::Conflict)
+    } else {
+        Ok(run_ids.join("\n"))
+    }
+}
+
+// API route to remove a job from the framework
+#[delete("/job/<name>/<id>")]
+fn remove_job(name: &str, id: &str, cronframe: &rocket::State<Arc<CronFrame>>) -> Status {
+    let job_id = cronframe
+        .jobs()
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|(_, job)| job.name() == name && job.id().to_string() == id)
+        .map(|(job_id, _)| *job_id);
+
+    match job_id.and_then(|job_id| cronframe.remove_job(job_id)) {
+        Some(_) => Status::Ok,
+        None => Status::NotFound,
+    }
+}
+
+// API route to remove the jobs with the given name, used by the cli tool
+#[delete("/job/<name>")]
+fn remove_jobs(name: &str, cronframe: &rocket::State<Arc<CronFrame>>) -> Status {
+    if cronframe.remove_jobs_by_name(name).is_empty() {
+        Status::NotFound
+    } else {
+        Status::Ok
+    }
+}
+
 // API route to start the scheduler
 #[get("/start_scheduler")]
 fn start_scheduler(cronframe: &rocket::State<Arc<CronFrame>>) {
@@ -315,11 +491,21 @@ fn stop_scheduler(cronframe: &rocket::State<Arc<CronFrame>>) {
     cronframe.stop_scheduler();
 }
 
-// API route to toggle the scheduling suspension for a job
+// API route to add a cli job, it responds with the error message if the job definition is not valid
 #[get("/add_cli_job/<expr>/<timeout>/<job>")]
-fn add_cli_job(expr: &str, timeout: &str, job: &str, cronframe: &rocket::State<Arc<CronFrame>>) {
-    let new_job = JobBuilder::cli_job(job, expr, timeout).build();
-    cronframe.add_job(new_job);
+fn add_cli_job(
+    expr: &str,
+    timeout: &str,
+    job: &str,
+    cronframe: &rocket::State<Arc<CronFrame>>,
+) -> Result<(), BadRequest<String>> {
+    let new_job = JobBuilder::cli_job(job, expr, timeout)
+        .build()
+        .map_err(|error| BadRequest(error.to_string()))?;
+    cronframe
+        .add_job(new_job)
+        .map_err(|error| BadRequest(error.to_string()))?;
+    Ok(())
 }
 
 // API route to stop the scheduler
@@ -488,6 +674,26 @@ pub const INDEX_TEMPLATE: &str = {
     </tr>
     {% endif %}
 </table>
+
+<table id="job_list">
+    <tr>
+        <th>
+            Worker Pool <div class="refresh" onclick="reloadPage()">⟳</div>
+        </th>
+    </tr>
+    <tr>
+        <td>Workers</td>
+        <td>{{workers.size}}</td>
+    </tr>
+    <tr>
+        <td>Busy Workers</td>
+        <td>{{workers.busy}}</td>
+    </tr>
+    <tr>
+        <td>Queued Runs</td>
+        <td>{{workers.queued}}</td>
+    </tr>
+</table>
 {% endblock content %}"#
 };
 
@@ -527,6 +733,12 @@ pub const JOB_TEMPLATE: &str = {
         <td>Type</td>
         <td colspan="2">{{job_info.type}} Job</td>
     </tr>
+    {% if job_info.tags != "" %}
+    <tr>
+        <td>Tags</td>
+        <td colspan="2">{{job_info.tags}}</td>
+    </tr>
+    {% endif %}
     {% if job_info.run_id != "None" %}
     <tr>
         <td>Run Id</td>
@@ -563,23 +775,74 @@ pub const JOB_TEMPLATE: &str = {
             {% endif %}
         </td>
     </tr>
+    <tr>
+        <td>Manual Run</td>
+        <td>
+            Run the job now, outside of its schedule
+        </td>
+        <td>
+            {% if job_info.status != "Suspended" %}
+            <button onclick="runNow()">Run Now</button>
+            {% endif %}
+        </td>
+    </tr>
+    <tr>
+        <td>Removal</td>
+        <td>
+            Remove the job from the framework and the job store
+        </td>
+        <td>
+            <button onclick="removeJob()">Remove Job</button>
+        </td>
+    </tr>
     <tr>
         <td>Fail History</td>
         <td colspan="2">
             {% if job_info.fail %}
             <div class="line_status_orange">Failed instances recorded</div>
+            {% if job_info.last_error != "" %}
+            Last error: {{job_info.last_error}}
+            {% endif %}
             {% else %}
             No failed instances recorded
             {% endif %}
         </td>
     </tr>
+    <tr>
+        <td>Overlap Policy</td>
+        <td colspan="2">
+            {{job_info.overlap}} ({{job_info.instances}} running)
+        </td>
+    </tr>
+    <tr>
+        <td>Max Runtime</td>
+        <td colspan="2">
+            {{job_info.max_runtime}}
+        </td>
+    </tr>
+    <tr>
+        <td>Retries</td>
+        <td>
+            {{job_info.retry}}
+        </td>
+        <td>
+            <input oninput="setRetries(this.value)" type="number" min="0" placeholder="enter number of retries">
+            <button onclick="updateRetries()">Update</button>
+        </td>
+    </tr>
+    <tr>
+        <td>Catch-up Policy</td>
+        <td colspan="2">
+            {{job_info.catch_up}}
+        </td>
+    </tr>
     <tr>
         <td>Schedule</td>
         <td>
             {{job_info.schedule}}
         </td>
         <td>
-            <input oninput="setSchedule(this.value)" type="text" placeholder="enter cron expression">
+            <input oninput="setSchedule(this.value)" type="text" placeholder="enter cron expression or date time">
             <button onclick="updateSchedule()">Update</button>
         </td>
     </tr>
@@ -598,6 +861,12 @@ pub const JOB_TEMPLATE: &str = {
             <button onclick="updateTimeout()">Update</button>
         </td>
     </tr>
+    <tr>
+        <td>Time Zone</td>
+        <td colspan="2">
+            {{job_info.timezone}}
+        </td>
+    </tr>
     <tr>
         <td>Upcoming</td>
         <td colspan="2">
@@ -607,12 +876,58 @@ pub const JOB_TEMPLATE: &str = {
                 <p>{{job_info.upcoming_utc}}</p>
                 {% if job_info.status != "Timed-Out" and job_info.status != "Suspended" %}
                 <p>{{job_info.upcoming_local}} (Local)</p>
+                <p>{{job_info.upcoming_zone}} ({{job_info.timezone}})</p>
                 {% endif %}
             {% endif %}
         </td>
     </tr>
 </table>
 
+<table id="job_list">
+    <tr>
+        <th colspan="8">
+            Run History
+        </th>
+    </tr>
+    {% if job_info.history | length == 0 %}
+    <tr>
+        <td colspan="8">No runs recorded</td>
+    </tr>
+    {% else %}
+    <tr>
+        <td>Run Id</td>
+        <td>Attempt</td>
+        <td>Origin</td>
+        <td>Scheduled</td>
+        <td>Start</td>
+        <td>End</td>
+        <td>Duration</td>
+        <td>Outcome</td>
+    </tr>
+    {% for run in job_info.history %}
+    <tr>
+        <td>{{run.run_id}}</td>
+        <td>{{run.attempt}}</td>
+        <td>{{run.origin}}</td>
+        <td>{{run.fire_time}}</td>
+        <td>{{run.start_time}}</td>
+        <td>{{run.end_time}}</td>
+        <td>{{run.duration}}</td>
+        <td>
+            {% if run.outcome == "Completed" %}
+            <div class="line_status_green">{{run.outcome}}</div>
+            {% else %}
+            <div class="line_status_orange">{{run.outcome}}</div>
+            {% endif %}
+            {% if run.error != "" %}
+            {{run.error}}
+            {% endif %}
+        </td>
+    </tr>
+    {% endfor %}
+    {% endif %}
+</table>
+
 <script>
     
 </script>
@@ -1303,6 +1618,7 @@ const stopScheduler = () => {
 
 let timeout = 0;
     let schedule = "* * * * * * *";
+    let retries = 0;
 
     const setTimeout = (value) => {
         console.log(value);
@@ -1325,6 +1641,27 @@ let timeout = 0;
         };
     }
 
+    const setRetries = (value) => {
+        console.log(value);
+        retries = value
+    };
+
+    const updateRetries = () => {
+        console.log("request to: " + window.location.href + "/retryset/" + retries);
+        const xhr = new XMLHttpRequest();
+        xhr.open("GET", window.location.href + "/retryset/" + retries);
+        xhr.send();
+        xhr.responseType = "json";
+        xhr.onload = () => {
+            if (xhr.readyState == 4 && xhr.status == 200) {
+                console.log(xhr.response);
+                location.reload();
+            } else {
+                console.log(`Error: ${xhr.status}`);
+            }
+        };
+    }
+
     const setSchedule = (value) => {
         console.log(value);
         schedule = value
@@ -1362,6 +1699,37 @@ let timeout = 0;
         };
     }
 
+    const runNow = () => {
+        console.log("request to: " + window.location.href + "/run_now");
+        const xhr = new XMLHttpRequest();
+        xhr.open("GET", window.location.href + "/run_now");
+        xhr.send();
+        xhr.responseType = "json";
+        xhr.onload = () => {
+            if (xhr.readyState == 4 && xhr.status == 200) {
+                console.log(xhr.response);
+                toast("Run Started");
+            } else {
+                console.log(`Error: ${xhr.status}`);
+            }
+        };
+    }
+
+    const removeJob = () => {
+        if (!confirm("Remove this job?")) return;
+        console.log("delete request to: " + window.location.href);
+        const xhr = new XMLHttpRequest();
+        xhr.open("DELETE", window.location.href);
+        xhr.send();
+        xhr.onload = () => {
+            if (xhr.readyState == 4 && xhr.status == 200) {
+                window.location.href = "/";
+            } else {
+                console.log(`Error: ${xhr.status}`);
+            }
+        };
+    }
+
     const copyToClipBoard = (element) => {
         var copyText = document.getElementById(element);
         navigator.clipboard.writeText(copyText.innerHTML);
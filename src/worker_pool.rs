@@ -0,0 +1,150 @@
+use crate::{cronframe::SchedulerMessage, cronjob::JobRun};
+use crossbeam_channel::Sender;
+use rocket::tokio::runtime::Handle;
+use std::{
+    cmp::Ordering,
+    collections::BinaryHeap,
+    sync::{Arc, Condvar, Mutex},
+};
+use uuid::Uuid;
+
+// a run of a job waiting for a free worker
+struct QueuedRun {
+    priority: i32,
+    seq: u64,
+    job_run: JobRun,
+}
+
+// higher priority runs go first, runs with the same priority go in submission order
+impl Ord for QueuedRun {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+impl PartialOrd for QueuedRun {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for QueuedRun {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for QueuedRun {}
+
+#[derive(Default)]
+struct PoolState {
+    queue: BinaryHeap<QueuedRun>,
+    seq: u64,
+    busy: usize,
+}
+
+// the state of the pool along with a condvar for available work and one for the pool going idle
+type SharedState = Arc<(Mutex<PoolState>, Condvar, Condvar)>;
+
+/// Fixed size pool of worker threads executing the runs of due jobs
+pub struct WorkerPool {
+    size: usize,
+    state: SharedState,
+}
+
+impl WorkerPool {
+    pub fn new(
+        size: usize,
+        scheduler_tx: Sender<(Uuid, SchedulerMessage)>,
+        runtime: Handle,
+    ) -> WorkerPool {
+        let size = size.max(1);
+        let state: SharedState = Arc::new((
+            Mutex::new(PoolState::default()),
+            Condvar::new(),
+            Condvar::new(),
+        ));
+
+        for index in 0..size {
+            let state = state.clone();
+            let scheduler_tx = scheduler_tx.clone();
+            let runtime = runtime.clone();
+
+            let worker = move || loop {
+                let (lock, work, idle) = &*state;
+
+                let queued_run = {
+                    let mut pool = lock.lock().expect("worker pool unwrap error in worker");
+                    loop {
+                        if let Some(queued_run) = pool.queue.pop() {
+                            pool.busy += 1;
+                            break queued_run;
+                        }
+                        pool = work.wait(pool).expect("worker pool unwrap error in worker");
+                    }
+                };
+
+                queued_run.job_run.run(scheduler_tx.clone(), &runtime);
+
+                let mut pool = lock.lock().expect("worker pool unwrap error in worker");
+                pool.busy -= 1;
+                if pool.busy == 0 && pool.queue.is_empty() {
+                    idle.notify_all();
+                }
+            };
+
+            std::thread::Builder::new()
+                .name(format!("cronframe-worker-{index}"))
+                .spawn(worker)
+                .expect("worker pool thread spawn error");
+        }
+
+        WorkerPool { size, state }
+    }
+
+    // it queues a run of the job, it is executed as soon as a worker is free
+    pub(crate) fn submit(&self, job_run: JobRun, priority: i32) {
+        let (lock, work, _) = &*self.state;
+        let mut pool = lock.lock().expect("worker pool unwrap error in submit");
+        pool.seq += 1;
+        let seq = pool.seq;
+        pool.queue.push(QueuedRun {
+            priority,
+            seq,
+            job_run,
+        });
+        work.notify_one();
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    // number of workers currently executing a run
+    pub fn busy(&self) -> usize {
+        self.state.0.lock().expect("worker pool unwrap error").busy
+    }
+
+    // number of runs waiting for a free worker
+    pub fn queued(&self) -> usize {
+        self.state
+            .0
+            .lock()
+            .expect("worker pool unwrap error")
+            .queue
+            .len()
+    }
+
+    // it blocks until there are no queued or executing runs
+    pub fn wait_idle(&self) {
+        let (lock, _, idle) = &*self.state;
+        let mut pool = lock.lock().expect("worker pool unwrap error in wait_idle");
+        while pool.busy > 0 || !pool.queue.is_empty() {
+            pool = idle
+                .wait(pool)
+                .expect("worker pool unwrap error in wait_idle");
+        }
+    }
+}
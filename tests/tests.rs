@@ -1,9 +1,17 @@
+
 #[macro_use]
 extern crate cronframe_macro;
 
 use chrono::{DateTime, Duration, Utc};
-use cronframe::{logger, ConfigData, CronFilter, CronFrame, CronFrameExpr, LoggerConfig, SchedulerConfig, ServerConfig};
-use std::{fs, ptr::addr_of_mut, sync::Once};
+use cronframe::{
+    logger, ConfigData, CronFilter, CronFrame, CronFrameExpr, LoggerConfig, SchedulerConfig,
+    ServerConfig,
+};
+use std::{
+    fs,
+    ptr::addr_of_mut,
+    sync::{Arc, Once},
+};
 
 static LOGGER_INIT: Once = Once::new();
 static mut LOGGER: Option<log4rs::Handle> = None;
@@ -120,6 +128,48 @@ pub fn init_logger(path: &str) {
     }
 }
 
+// the configuration of a test, with the default web server and no logger of its own
+pub fn test_config(scheduler: SchedulerConfig) -> ConfigData {
+    ConfigData {
+        webserver: ServerConfig::default(),
+        logger: LoggerConfig::disabled(),
+        scheduler,
+    }
+}
+
+// it sets the log file of a test and returns a framework with the given scheduler configuration
+pub fn test_frame(file_path: &str, scheduler: SchedulerConfig) -> Arc<CronFrame> {
+    init_logger(file_path);
+    CronFrame::with_config(test_config(scheduler)).unwrap()
+}
+
+// it returns the content of the log file of a test, the test fails without it
+pub fn read_log(file_path: &str) -> String {
+    let file_content = fs::read_to_string(file_path);
+    assert!(file_content.is_ok(), "no log file at {file_path}");
+    file_content.unwrap()
+}
+
+// the lines of a log about the given job
+pub fn job_lines<'a>(file_content: &'a str, job_name: &str) -> impl Iterator<Item = &'a str> {
+    let job_name = format!("{job_name} ");
+    file_content
+        .lines()
+        .filter(move |line| line.contains(&job_name))
+}
+
+// it waits for a condition to hold for at most the given time, returning whether it held
+pub fn wait_for(timeout: Duration, condition: impl Fn() -> bool) -> bool {
+    let deadline = Utc::now() + timeout;
+    while !condition() {
+        if Utc::now() > deadline {
+            return false;
+        }
+        std::thread::sleep(Duration::milliseconds(50).to_std().unwrap());
+    }
+    true
+}
+
 pub fn test_job(
     file_path: &str,
     job_filter: CronFilter,
@@ -129,25 +179,18 @@ pub fn test_job(
     timeout: Duration,
     should_fail: bool,
 ) {
-    init_logger(file_path);
-
-    let config = ConfigData {
-        webserver: ServerConfig::default(),
-        logger: LoggerConfig::disabled(),
-        scheduler: SchedulerConfig::default(),
-    };
-
-    let cronframe = CronFrame::with_config(config).unwrap();
+    let cronframe = test_frame(file_path, SchedulerConfig::default());
 
     let expr_fail = CronFrameExpr::new("0", "0/5", "*", "*", "*", "*", "*", 0);
     let expr_timeout = CronFrameExpr::new("0", "*/5", "*", "*", "*", "*", "*", 720000);
     let expr_std = CronFrameExpr::new("0", "0/5", "*", "*", "*", "*", "*", 0);
 
-    let mut mt_fail = MethodFail::new_cron_obj(expr_fail);
-    let mut mt_timeout = MethodTimeout::new_cron_obj(expr_timeout);
-    let mut mt_std = MethodStd::new_cron_obj(expr_std);
+    let mt_fail = MethodFail::new_cron_obj(expr_fail);
+    let mt_timeout = MethodTimeout::new_cron_obj(expr_timeout);
+    let mt_std = MethodStd::new_cron_obj(expr_std);
 
-    match job_filter {
+    // the handle of the cron object keeps its method jobs until the end of the test
+    let _cron_obj: Option<Box<dyn std::any::Any>> = match job_filter {
         CronFilter::Function => {
             if should_fail {
                 FunctionFail::cf_gather_fn(cronframe.clone());
@@ -156,21 +199,22 @@ pub fn test_job(
             } else {
                 FunctionStd::cf_gather_fn(cronframe.clone());
             }
+            None
         }
         CronFilter::Method => {
             if should_fail {
-                mt_fail.cf_gather(cronframe.clone());
+                Some(Box::new(mt_fail.cf_gather(cronframe.clone())))
             } else if timeout > Duration::seconds(0) {
-                mt_timeout.cf_gather(cronframe.clone());
+                Some(Box::new(mt_timeout.cf_gather(cronframe.clone())))
             } else {
-                mt_std.cf_gather(cronframe.clone());
+                Some(Box::new(mt_std.cf_gather(cronframe.clone())))
             }
         }
-        _ => (), // no additional stuff to do if global job
-    }
+        _ => None, // no additional stuff to do if global job
+    };
 
     // execute for a given time
-    let mut first_run: DateTime<Utc> = cronframe
+    let first_run: DateTime<Utc> = cronframe
         .jobs()
         .lock()
         .unwrap()
@@ -188,12 +232,8 @@ pub fn test_job(
     let start_time = Utc::now();
     let end_time = start_time + duration;
 
+    // the scheduler fires the upcoming run even when it is only a few ms away
     println!("difference = {}", first_run - start_time);
-    if first_run - start_time <= Duration::milliseconds(500) {
-        println!("OLD First Run = {first_run}");
-        first_run = first_run + interval;
-        println!("NEW First Run = {first_run}");
-    }
 
     println!("START TIME IS: {start_time}");
     println!("END TIME IS: {end_time}");
@@ -206,9 +246,7 @@ pub fn test_job(
 
     // we need to get the current log file
     // if we don't have it, test fails
-    let file_content = fs::read_to_string(file_path);
-    assert_eq!(file_content.is_ok(), true);
-    let file_content = file_content.unwrap();
+    let file_content = read_log(file_path);
 
     // if we have the file content then we check its contents
     // the first check is to see if there are executions
@@ -474,3 +512,2014 @@ mod method {
         );
     }
 }
+
+mod misfire {
+    use crate::{job_lines, read_log, test_frame, wait_for};
+    use chrono::{Duration, Timelike, Utc};
+    use cronframe::{JobBuilder, JobOptions, MisfirePolicy, SchedulerConfig};
+
+    fn misfire_job() {
+        println!("call from misfire job");
+    }
+
+    // it sleeps until the middle of a second, far from the fire times of a job due every second
+    fn sleep_until_mid_second() {
+        let millis = Utc::now().timestamp_subsec_millis() as i64;
+        let wait = Duration::milliseconds((1500 - millis) % 1000);
+        std::thread::sleep(wait.to_std().unwrap());
+    }
+
+    // it returns the number of runs executed for the 3 fire times missed while the scheduler was stopped
+    pub fn test_misfire(file_path: &str, job_name: &str, policy: MisfirePolicy) -> usize {
+        let cronframe = test_frame(file_path, SchedulerConfig::default());
+
+        let job = JobBuilder::global_job(job_name, misfire_job, "* * * * * * *", "0")
+            .with_options(JobOptions::new().misfire(policy))
+            .build()
+            .unwrap();
+        let job_id = job.id();
+        cronframe.add_job(job).unwrap();
+
+        // the runs due while the scheduler is stopped are misfires
+        cronframe.start_scheduler();
+        assert!(wait_for(Duration::seconds(3), || !cronframe
+            .history(job_id)
+            .is_empty()));
+        sleep_until_mid_second();
+        cronframe.stop_scheduler();
+        let stopped_at = Utc::now();
+        std::thread::sleep(Duration::seconds(3).to_std().unwrap());
+        let restarted_at = Utc::now();
+        cronframe.start_scheduler();
+        std::thread::sleep(Duration::seconds(2).to_std().unwrap());
+        cronframe.quit();
+
+        let file_content = read_log(file_path);
+
+        assert_eq!(
+            job_lines(&file_content, job_name)
+                .filter(|line| line.contains("Misfire"))
+                .count(),
+            3,
+            "not 3 misfires in the log file"
+        );
+
+        // every missed fire time is at least 400ms late once the scheduler restarts
+        assert!((restarted_at - stopped_at).num_milliseconds() < 3100);
+        assert!(restarted_at.nanosecond() >= 400_000_000);
+
+        cronframe
+            .history(job_id)
+            .iter()
+            .filter(|record| record.fire_time > stopped_at && record.fire_time < restarted_at)
+            .count()
+    }
+
+    #[test]
+    fn misfire_fire_once() {
+        let runs = test_misfire(
+            "log/misfire_fire_once.log",
+            "my_misfire_job_once",
+            MisfirePolicy::FireOnce,
+        );
+        assert_eq!(runs, 1);
+    }
+
+    #[test]
+    fn misfire_fire_all() {
+        let runs = test_misfire(
+            "log/misfire_fire_all.log",
+            "my_misfire_job_all",
+            MisfirePolicy::FireAll,
+        );
+        assert_eq!(runs, 3);
+    }
+
+    #[test]
+    fn misfire_skip() {
+        let runs = test_misfire(
+            "log/misfire_skip.log",
+            "my_misfire_job_skip",
+            MisfirePolicy::Skip,
+        );
+        assert_eq!(runs, 0);
+    }
+}
+
+mod overlap {
+    use crate::{job_lines, read_log, test_frame};
+    use chrono::Duration;
+    use cronframe::{JobBuilder, JobOptions, OverlapPolicy, SchedulerConfig};
+
+    fn overlap_job() {
+        std::thread::sleep(Duration::milliseconds(2500).to_std().unwrap());
+    }
+
+    // it returns the highest number of runs going at the same time and of runs waiting for a slot
+    pub fn test_overlap(
+        file_path: &str,
+        job_name: &str,
+        policy: OverlapPolicy,
+        expected: &str,
+    ) -> (usize, usize) {
+        let cronframe = test_frame(file_path, SchedulerConfig::default());
+
+        // the job lasts longer than the interval between its runs
+        let job = JobBuilder::global_job(job_name, overlap_job, "* * * * * * *", "0")
+            .with_options(JobOptions::new().overlap(policy))
+            .build()
+            .unwrap();
+        let job_id = job.id();
+        cronframe.add_job(job).unwrap();
+
+        cronframe.start_scheduler();
+
+        let mut max_running = 0;
+        let mut max_pending = 0;
+        for _ in 0..50 {
+            std::thread::sleep(Duration::milliseconds(100).to_std().unwrap());
+            let cron_jobs = cronframe.jobs().lock().unwrap();
+            max_running = max_running.max(cron_jobs[&job_id].run_ids().len());
+            max_pending = max_pending.max(cron_jobs[&job_id].pending_runs());
+        }
+        cronframe.quit();
+
+        let file_content = read_log(file_path);
+
+        assert!(
+            job_lines(&file_content, job_name).any(|line| line.contains(expected)),
+            "no \"{expected}\" in the log file"
+        );
+
+        (max_running, max_pending)
+    }
+
+    #[test]
+    fn overlap_forbid() {
+        let (max_running, max_pending) = test_overlap(
+            "log/overlap_forbid.log",
+            "my_overlap_job_forbid",
+            OverlapPolicy::Forbid,
+            "Overlap Skipped",
+        );
+        assert_eq!(max_running, 1);
+        assert_eq!(max_pending, 0);
+    }
+
+    #[test]
+    fn overlap_queue() {
+        let (max_running, max_pending) = test_overlap(
+            "log/overlap_queue.log",
+            "my_overlap_job_queue",
+            OverlapPolicy::Queue,
+            "Overlap Queued",
+        );
+        assert_eq!(max_running, 1);
+        assert!(max_pending > 0, "no run waited for the previous one");
+    }
+
+    #[test]
+    fn overlap_allow() {
+        let (max_running, max_pending) = test_overlap(
+            "log/overlap_allow.log",
+            "my_overlap_job_allow",
+            OverlapPolicy::Allow(2),
+            "Overlap Skipped - Allow(2)",
+        );
+        assert_eq!(max_running, 2);
+        assert_eq!(max_pending, 0);
+    }
+}
+
+mod worker_pool {
+    use crate::test_frame;
+    use chrono::Duration;
+    use cronframe::{JobBuilder, JobOptions, SchedulerConfig};
+
+    fn pool_job() {
+        std::thread::sleep(Duration::milliseconds(1500).to_std().unwrap());
+    }
+
+    #[test]
+    fn worker_pool_bounded() {
+        let cronframe = test_frame(
+            "log/worker_pool_bounded.log",
+            SchedulerConfig {
+                workers: 1,
+                ..Default::default()
+            },
+        );
+        assert_eq!(cronframe.workers(), 1);
+
+        // two jobs due every second for a single worker
+        for (name, priority) in [("my_pool_job_low", 0), ("my_pool_job_high", 10)] {
+            let job = JobBuilder::global_job(name, pool_job, "* * * * * * *", "0")
+                .with_options(JobOptions::new().priority(priority))
+                .build()
+                .unwrap();
+            cronframe.add_job(job).unwrap();
+        }
+
+        cronframe.start_scheduler();
+
+        let mut max_queued = 0;
+        for _ in 0..16 {
+            std::thread::sleep(Duration::milliseconds(250).to_std().unwrap());
+            assert!(cronframe.busy_workers() <= 1);
+            max_queued = max_queued.max(cronframe.queue_depth());
+        }
+        assert!(max_queued > 0, "no run was queued for a free worker");
+
+        // quitting waits for the queued runs to be executed
+        cronframe.quit();
+        assert_eq!(cronframe.busy_workers(), 0);
+        assert_eq!(cronframe.queue_depth(), 0);
+    }
+}
+
+mod max_runtime {
+    use crate::{job_lines, read_log, test_frame, wait_for};
+    use chrono::Duration;
+    use cronframe::{CancellationToken, JobBuilder, JobOptions, RunOutcome, SchedulerConfig};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // a job that would run for 10 seconds unless cancelled
+    fn long_job() {
+        for _ in 0..100 {
+            if CancellationToken::current().is_some_and(|token| token.is_cancelled()) {
+                return;
+            }
+            std::thread::sleep(Duration::milliseconds(100).to_std().unwrap());
+        }
+    }
+
+    #[test]
+    fn max_runtime_exceeded() {
+        let file_path = "log/max_runtime_exceeded.log";
+        let job_name = "my_max_runtime_job";
+        let cronframe = test_frame(file_path, SchedulerConfig::default());
+
+        let job = JobBuilder::global_job(job_name, long_job, "0/5 * * * * * *", "0")
+            .with_options(JobOptions::new().max_runtime(Duration::milliseconds(1000)))
+            .build()
+            .unwrap();
+        let job_id = job.id();
+        cronframe.add_job(job).unwrap();
+
+        cronframe.start_scheduler();
+        let timed_out = wait_for(Duration::seconds(8), || {
+            cronframe
+                .history(job_id)
+                .iter()
+                .any(|record| record.outcome == RunOutcome::TimedOut)
+        });
+        cronframe.quit();
+        assert!(timed_out, "no timed-out run recorded");
+
+        let file_content = read_log(file_path);
+
+        assert!(
+            job_lines(&file_content, job_name).any(|line| line.contains("Max Runtime Exceeded")),
+            "no \"Max Runtime Exceeded\" in the log file"
+        );
+    }
+
+    // a job that runs for 4 seconds without ever checking its token
+    fn unchecked_job() {
+        std::thread::sleep(Duration::seconds(4).to_std().unwrap());
+    }
+
+    #[test]
+    fn max_runtime_unchecked() {
+        let file_path = "log/max_runtime_unchecked.log";
+        let job_name = "my_unchecked_job";
+        let cronframe = test_frame(file_path, SchedulerConfig::default());
+
+        let job = JobBuilder::global_job(job_name, unchecked_job, "* * * * * * *", "0")
+            .with_options(JobOptions::new().max_runtime(Duration::milliseconds(1000)))
+            .build()
+            .unwrap();
+        let job_id = job.id();
+        cronframe.add_job(job).unwrap();
+
+        cronframe.start_scheduler();
+        wait_for(Duration::seconds(4), || {
+            cronframe
+                .history(job_id)
+                .iter()
+                .any(|record| record.outcome == RunOutcome::TimedOut)
+        });
+
+        // the timeout is recorded at the deadline while the job is still sleeping
+        let history = cronframe.history(job_id);
+        cronframe.quit();
+
+        assert!(
+            history
+                .iter()
+                .any(|record| record.outcome == RunOutcome::TimedOut
+                    && record.duration() < Duration::seconds(2)),
+            "no timed-out run recorded at the deadline"
+        );
+    }
+
+    static RUNNING: AtomicUsize = AtomicUsize::new(0);
+    static MAX_RUNNING: AtomicUsize = AtomicUsize::new(0);
+
+    // a job that runs for 1.5 seconds without ever checking its token
+    fn overrun_job() {
+        let running = RUNNING.fetch_add(1, Ordering::SeqCst) + 1;
+        MAX_RUNNING.fetch_max(running, Ordering::SeqCst);
+        std::thread::sleep(Duration::milliseconds(1500).to_std().unwrap());
+        RUNNING.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    #[test]
+    fn max_runtime_retry() {
+        let file_path = "log/max_runtime_retry.log";
+        let job_name = "my_overrun_job";
+        let cronframe = test_frame(file_path, SchedulerConfig::default());
+
+        // a schedule that is never due during the test
+        let job = JobBuilder::global_job(job_name, overrun_job, "0 0 0 1 1 * 2099", "0")
+            .with_options(
+                JobOptions::new()
+                    .max_runtime(Duration::milliseconds(500))
+                    .retries(1),
+            )
+            .build()
+            .unwrap();
+        let job_id = job.id();
+        cronframe.add_job(job).unwrap();
+
+        cronframe.start_scheduler();
+        cronframe.trigger(job_id).expect("manual run not started");
+
+        wait_for(Duration::seconds(10), || {
+            cronframe.history(job_id).len() >= 2
+        });
+        let history = cronframe.history(job_id);
+        cronframe.quit();
+
+        // the retry starts only once the worker of the overrun attempt is free
+        assert_eq!(history.len(), 2);
+        assert!(history
+            .iter()
+            .all(|record| record.outcome == RunOutcome::TimedOut));
+        assert!(history[1].start_time >= history[0].start_time + Duration::milliseconds(1500));
+        assert_eq!(MAX_RUNNING.load(Ordering::SeqCst), 1);
+    }
+}
+
+mod cancellation {
+    use crate::{job_lines, read_log, test_frame, wait_for};
+    use chrono::{Duration, Utc};
+    use cronframe::{
+        cron_impl, cron_obj, mt_job, CronFrameExpr, JobBuilder, JobContext, SchedulerConfig,
+    };
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static CONTEXT_EXECUTIONS: AtomicUsize = AtomicUsize::new(0);
+
+    #[cron_obj]
+    #[derive(Debug)]
+    struct ContextJobs {
+        expr: CronFrameExpr,
+    }
+
+    #[cron_impl]
+    impl ContextJobs {
+        // a job that would run for 30 seconds unless cancelled
+        #[mt_job(expr = "expr")]
+        fn my_context_job(self, ctx: &JobContext) {
+            CONTEXT_EXECUTIONS.fetch_add(1, Ordering::SeqCst);
+            for _ in 0..300 {
+                if ctx.is_cancelled() {
+                    return;
+                }
+                std::thread::sleep(Duration::milliseconds(100).to_std().unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn cancellation_on_quit() {
+        let file_path = "log/cancellation_on_quit.log";
+        let cronframe = test_frame(file_path, SchedulerConfig::default());
+
+        let expr = CronFrameExpr::new("*", "*", "*", "*", "*", "*", "*", 0);
+        let _context_jobs = ContextJobs::new_cron_obj(expr).cf_gather_mt(cronframe.clone());
+
+        cronframe.start_scheduler();
+        assert!(wait_for(Duration::seconds(3), || {
+            CONTEXT_EXECUTIONS.load(Ordering::SeqCst) > 0
+        }));
+
+        // quitting cancels the running job instead of waiting for it to end
+        let quit_time = Utc::now();
+        cronframe.quit();
+        assert!(Utc::now() - quit_time < Duration::seconds(5));
+
+        let file_content = read_log(file_path);
+
+        assert!(
+            job_lines(&file_content, "my_context_job").any(|line| line.contains("Cancelled")),
+            "no \"Cancelled\" in the log file"
+        );
+    }
+
+    static QUEUED_EXECUTIONS: AtomicUsize = AtomicUsize::new(0);
+
+    // a job that would run for 30 seconds unless cancelled
+    fn my_queued_job(ctx: &JobContext) {
+        QUEUED_EXECUTIONS.fetch_add(1, Ordering::SeqCst);
+        for _ in 0..300 {
+            if ctx.is_cancelled() {
+                return;
+            }
+            std::thread::sleep(Duration::milliseconds(100).to_std().unwrap());
+        }
+    }
+
+    #[test]
+    fn cancellation_of_queued_runs() {
+        let file_path = "log/cancellation_of_queued_runs.log";
+        let cronframe = test_frame(
+            file_path,
+            SchedulerConfig {
+                workers: 1,
+                ..SchedulerConfig::default()
+            },
+        );
+
+        // a schedule that is never due during the test
+        let job = JobBuilder::function_job_with_context(
+            "my_queued_job",
+            my_queued_job,
+            "0 0 0 1 1 * 2099",
+            "0",
+        )
+        .build()
+        .unwrap();
+        let job_id = job.id();
+        cronframe.add_job(job).unwrap();
+
+        // the single worker takes the first run, the others wait in the queue
+        cronframe.start_scheduler();
+        for _ in 0..4 {
+            cronframe.trigger(job_id).expect("manual run not started");
+        }
+
+        wait_for(Duration::seconds(5), || {
+            QUEUED_EXECUTIONS.load(Ordering::SeqCst) > 0
+        });
+        assert_eq!(cronframe.queue_depth(), 3);
+
+        // quitting cancels the queued runs, which never start
+        let quit_time = Utc::now();
+        cronframe.quit();
+        assert!(Utc::now() - quit_time < Duration::seconds(5));
+        assert_eq!(QUEUED_EXECUTIONS.load(Ordering::SeqCst), 1);
+        assert_eq!(cronframe.queue_depth(), 0);
+    }
+}
+
+mod context {
+    use crate::{job_lines, read_log, test_frame, wait_for};
+    use chrono::Duration;
+    use cronframe::{info, JobBuilder, JobContext, SchedulerConfig};
+
+    fn context_job(ctx: &JobContext) {
+        info!(
+            "context job {} - run_uuid#{} - attempt {} - fired {} - started {}",
+            ctx.job_name(),
+            ctx.run_id(),
+            ctx.attempt(),
+            ctx.fire_time(),
+            ctx.start_time()
+        );
+    }
+
+    #[test]
+    fn context_run_metadata() {
+        let file_path = "log/context_run_metadata.log";
+        let job_name = "my_context_metadata_job";
+        let cronframe = test_frame(file_path, SchedulerConfig::default());
+
+        let job = JobBuilder::global_job_with_context(job_name, context_job, "* * * * * * *", "0")
+            .build()
+            .unwrap();
+        let job_id = job.id();
+        cronframe.add_job(job).unwrap();
+
+        cronframe.start_scheduler();
+        wait_for(Duration::seconds(4), || {
+            cronframe.history(job_id).len() >= 2
+        });
+        cronframe.quit();
+
+        let file_content = read_log(file_path);
+
+        // the job sees the same run id the scheduler logs
+        let run_ids: Vec<_> = job_lines(&file_content, job_name)
+            .filter(|line| line.contains("context job "))
+            .map(|line| {
+                assert!(line.contains("attempt 1"));
+                line.split("run_uuid#")
+                    .nth(1)
+                    .unwrap()
+                    .split(' ')
+                    .next()
+                    .unwrap()
+            })
+            .collect();
+
+        assert!(!run_ids.is_empty(), "no context job output in the log file");
+        for run_id in run_ids {
+            assert!(job_lines(&file_content, job_name)
+                .any(|line| line.contains(&format!("job name@{job_name} "))
+                    && line.contains(&format!("run_uuid#{run_id} - Execution"))));
+        }
+    }
+}
+
+mod fallible {
+    use crate::{job_lines, read_log, test_frame, wait_for};
+    use chrono::Duration;
+    use cronframe::{
+        cron_impl, cron_obj, fn_job, mt_job, CronFrameExpr, JobContext, SchedulerConfig,
+    };
+
+    #[cron_obj]
+    #[derive(Debug)]
+    struct FallibleJobs {
+        expr: CronFrameExpr,
+    }
+
+    #[cron_impl]
+    impl FallibleJobs {
+        #[fn_job(expr = "* * * * * * *", timeout = "0")]
+        fn my_fallible_fn_job() -> Result<(), std::num::ParseIntError> {
+            "not a number".parse::<u32>()?;
+            Ok(())
+        }
+
+        #[mt_job(expr = "expr")]
+        fn my_fallible_mt_job(self, ctx: &JobContext) -> Result<(), String> {
+            Err(format!("failed run {}", ctx.run_id()))
+        }
+    }
+
+    #[test]
+    fn fallible_job_error() {
+        let file_path = "log/fallible_job_error.log";
+        let cronframe = test_frame(file_path, SchedulerConfig::default());
+
+        let expr = CronFrameExpr::new("*", "*", "*", "*", "*", "*", "*", 0);
+        let _fallible_jobs = FallibleJobs::new_cron_obj(expr).cf_gather(cronframe.clone());
+
+        cronframe.start_scheduler();
+        wait_for(Duration::seconds(3), || {
+            cronframe
+                .jobs()
+                .lock()
+                .unwrap()
+                .values()
+                .filter(|cron_job| cron_job.name().starts_with("my_fallible"))
+                .all(|cron_job| cron_job.failed())
+        });
+        cronframe.quit();
+
+        // the error is recorded on the job
+        for (_, cron_job) in cronframe.jobs().lock().unwrap().iter() {
+            if cron_job.name().starts_with("my_fallible") {
+                assert!(cron_job.failed());
+                assert!(cron_job.last_error().is_some());
+            }
+        }
+
+        let file_content = read_log(file_path);
+
+        assert!(
+            job_lines(&file_content, "my_fallible_fn_job")
+                .any(|line| line.contains("Failed - invalid digit found in string")),
+            "no fn job failure in the log file"
+        );
+        assert!(
+            job_lines(&file_content, "my_fallible_mt_job")
+                .any(|line| line.contains("Failed - failed run")),
+            "no method job failure in the log file"
+        );
+    }
+}
+
+mod async_jobs {
+    use crate::{job_lines, read_log, test_frame, wait_for};
+    use chrono::Duration;
+    use cronframe::{
+        cron_impl, cron_obj, fn_job, mt_job, CronFrameExpr, JobContext, SchedulerConfig,
+    };
+    use rocket::tokio;
+
+    #[cron_obj]
+    #[derive(Debug)]
+    struct AsyncJobs {
+        expr: CronFrameExpr,
+    }
+
+    #[cron_impl]
+    impl AsyncJobs {
+        #[fn_job(expr = "* * * * * * *", timeout = "0")]
+        async fn my_async_fn_job() {
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+
+        #[mt_job(expr = "expr")]
+        async fn my_async_mt_job(self, ctx: &JobContext) -> Result<(), String> {
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            Err(format!("async failure on attempt {}", ctx.attempt()))
+        }
+    }
+
+    #[test]
+    fn async_job_run() {
+        let file_path = "log/async_job_run.log";
+        let cronframe = test_frame(file_path, SchedulerConfig::default());
+
+        let expr = CronFrameExpr::new("*", "*", "*", "*", "*", "*", "*", 0);
+        let _async_jobs = AsyncJobs::new_cron_obj(expr).cf_gather(cronframe.clone());
+
+        cronframe.start_scheduler();
+        wait_for(Duration::seconds(3), || {
+            cronframe
+                .jobs()
+                .lock()
+                .unwrap()
+                .values()
+                .filter(|cron_job| cron_job.name().starts_with("my_async"))
+                .all(|cron_job| !cron_job.history().is_empty())
+        });
+        cronframe.quit();
+
+        let file_content = read_log(file_path);
+
+        assert!(
+            job_lines(&file_content, "my_async_fn_job").any(|line| line.contains("Completed")),
+            "no async fn job completion in the log file"
+        );
+        assert!(
+            job_lines(&file_content, "my_async_mt_job")
+                .any(|line| line.contains("Failed - async failure")),
+            "no async method job failure in the log file"
+        );
+    }
+}
+
+mod retries {
+    use crate::{job_lines, read_log, test_frame, wait_for};
+    use chrono::Duration;
+    use cronframe::{cron_impl, cron_obj, fn_job, JobContext, RunOutcome, SchedulerConfig};
+
+    #[cron_obj]
+    #[derive(Debug)]
+    struct RetryJobs;
+
+    #[cron_impl]
+    impl RetryJobs {
+        #[fn_job(
+            expr = "0/5 * * * * * *",
+            timeout = "0",
+            retries = 2,
+            backoff = "fixed:200ms"
+        )]
+        fn my_always_failing_job() -> Result<(), String> {
+            Err("always failing".to_string())
+        }
+
+        #[fn_job(
+            expr = "0/5 * * * * * *",
+            timeout = "0",
+            retries = 1,
+            backoff = "exp:100ms",
+            jitter = true
+        )]
+        fn my_flaky_job(ctx: &JobContext) -> Result<(), String> {
+            if ctx.attempt() < 2 {
+                return Err("first attempt failing".to_string());
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn retry_attempts() {
+        let file_path = "log/retry_attempts.log";
+        let cronframe = test_frame(file_path, SchedulerConfig::default());
+
+        let _retry_jobs = RetryJobs::new_cron_obj().cf_gather(cronframe.clone());
+
+        // all the attempts of the first run of both jobs are over
+        cronframe.start_scheduler();
+        wait_for(Duration::seconds(7), || {
+            cronframe.jobs().lock().unwrap().values().all(|cron_job| {
+                match cron_job.name().as_str() {
+                    "my_always_failing_job" => cron_job.history().len() >= 3,
+                    "my_flaky_job" => cron_job
+                        .history()
+                        .iter()
+                        .any(|record| record.outcome == RunOutcome::Completed),
+                    _ => true,
+                }
+            })
+        });
+        cronframe.quit();
+
+        // only the job failing its last attempt is marked as failed
+        for (_, cron_job) in cronframe.jobs().lock().unwrap().iter() {
+            match cron_job.name().as_str() {
+                "my_always_failing_job" => assert!(cron_job.failed()),
+                "my_flaky_job" => assert!(!cron_job.failed()),
+                _ => {}
+            }
+        }
+
+        let file_content = read_log(file_path);
+
+        assert!(
+            job_lines(&file_content, "my_always_failing_job")
+                .any(|line| line.contains("Execution - Attempt 3/3")),
+            "no last attempt in the log file"
+        );
+        assert!(
+            job_lines(&file_content, "my_flaky_job")
+                .any(|line| line.contains("Retry - Attempt 2/2")),
+            "no retry in the log file"
+        );
+        assert!(
+            job_lines(&file_content, "my_flaky_job").any(|line| line.contains("Completed")),
+            "no completion after the retry in the log file"
+        );
+    }
+}
+
+mod history {
+    use crate::{job_lines, read_log, test_frame, wait_for};
+    use chrono::Duration;
+    use cronframe::{cron_impl, cron_obj, fn_job, RunOutcome, SchedulerConfig};
+
+    #[cron_obj]
+    #[derive(Debug)]
+    struct HistoryJobs;
+
+    #[cron_impl]
+    impl HistoryJobs {
+        #[fn_job(expr = "* * * * * * *", timeout = "0")]
+        fn my_history_job() -> Result<(), String> {
+            Err("recorded error".to_string())
+        }
+    }
+
+    #[test]
+    fn history_records() {
+        let file_path = "log/history_records.log";
+        let cronframe = test_frame(
+            file_path,
+            SchedulerConfig {
+                history_size: 2,
+                ..SchedulerConfig::default()
+            },
+        );
+
+        let _history_jobs = HistoryJobs::new_cron_obj().cf_gather(cronframe.clone());
+
+        let job_id = cronframe
+            .jobs()
+            .lock()
+            .unwrap()
+            .values()
+            .find(|cron_job| cron_job.name() == "my_history_job")
+            .map(|cron_job| cron_job.id())
+            .unwrap();
+
+        // a third run pushes the first one out of the history
+        cronframe.start_scheduler();
+        wait_for(Duration::seconds(5), || {
+            job_lines(&read_log(file_path), "my_history_job")
+                .filter(|line| line.contains("Failed"))
+                .count()
+                >= 3
+        });
+        cronframe.quit();
+
+        // the history keeps only the latest runs
+        let history = cronframe.history(job_id);
+        assert_eq!(history.len(), 2);
+
+        for record in &history {
+            assert_eq!(record.outcome, RunOutcome::Failed);
+            assert_eq!(record.error.as_deref(), Some("recorded error"));
+            assert!(record.fire_time <= record.start_time);
+            assert!(record.start_time <= record.end_time);
+        }
+        assert!(history[0].start_time < history[1].start_time);
+    }
+}
+
+mod job_store {
+    use crate::{init_logger, test_config, wait_for};
+    use chrono::Duration;
+    use cronframe::{
+        cron_impl, cron_obj, mt_job, ConfigData, CronFrame, CronFrameExpr, FileStore, JobBuilder,
+        JobOverrides, JobStore, RunOrigin, RunOutcome, SchedulerConfig, StoredJob, StoredRun,
+    };
+    use std::fs;
+
+    #[cron_obj]
+    #[derive(Debug)]
+    struct StoreJobs {
+        expr: CronFrameExpr,
+    }
+
+    #[cron_impl]
+    impl StoreJobs {
+        #[mt_job(expr = "expr")]
+        fn my_stored_job(self) {
+            println!("call from stored job");
+        }
+    }
+
+    fn config() -> ConfigData {
+        test_config(SchedulerConfig::default())
+    }
+
+    fn stored_job_id(cronframe: &CronFrame) -> uuid::Uuid {
+        cronframe
+            .jobs()
+            .lock()
+            .unwrap()
+            .values()
+            .find(|cron_job| cron_job.name() == "my_stored_job")
+            .map(|cron_job| cron_job.id())
+            .unwrap()
+    }
+
+    #[test]
+    fn job_store_reload() {
+        let file_path = "log/job_store_reload.log";
+        init_logger(file_path);
+
+        let store_path = "log/job_store_reload.json";
+        let _ = fs::remove_file(store_path);
+
+        // first instance, the job runs and its schedule is changed through the web server
+        let cronframe =
+            CronFrame::with_store(config(), FileStore::new(store_path).unwrap()).unwrap();
+
+        let expr = CronFrameExpr::new("*", "*", "*", "*", "*", "*", "*", 0);
+        let store_jobs = StoreJobs::new_cron_obj(expr.clone()).cf_gather(cronframe.clone());
+
+        let job_id = stored_job_id(&cronframe);
+        cronframe.start_scheduler();
+        wait_for(Duration::seconds(3), || {
+            !cronframe.history(job_id).is_empty()
+        });
+
+        let update =
+            format!("http://127.0.0.1:8098/job/my_stored_job/{job_id}/schedset/0 0 3 * * * *");
+        assert!(reqwest::blocking::get(update).is_ok());
+        cronframe.quit();
+        drop(store_jobs);
+
+        // a CLI job added by another instance
+        let store = FileStore::new(store_path).unwrap();
+        store
+            .save(&StoredJob {
+                id: uuid::Uuid::new_v4().to_string(),
+                name: "my_stored_cli_job".to_string(),
+                job_type: "CLI".to_string(),
+                cron_expr: "0 0 4 * * * *".to_string(),
+                timeout: 0,
+                suspended: true,
+                overrides: Default::default(),
+                history: Vec::new(),
+                last_fire: None,
+            })
+            .unwrap();
+
+        // second instance, the state of the job is reloaded from the store
+        let cronframe = CronFrame::with_store(config(), store).unwrap();
+        let _store_jobs = StoreJobs::new_cron_obj(expr).cf_gather(cronframe.clone());
+
+        let job_id = stored_job_id(&cronframe);
+        {
+            let cron_jobs = cronframe.jobs().lock().unwrap();
+            let cron_job = &cron_jobs[&job_id];
+            assert_eq!(cron_job.schedule(), "0 0 3 * * * *");
+
+            let cli_job = cron_jobs
+                .values()
+                .find(|cron_job| cron_job.name() == "my_stored_cli_job")
+                .expect("the CLI job was not restored");
+            assert_eq!(cli_job.type_to_string(), "CLI");
+            assert!(cli_job.suspended());
+        }
+        assert!(!cronframe.history(job_id).is_empty());
+
+        cronframe.quit();
+    }
+
+    #[test]
+    fn job_store_instances() {
+        let file_path = "log/job_store_instances.log";
+        init_logger(file_path);
+
+        let store_path = "log/job_store_instances.json";
+        let _ = fs::remove_file(store_path);
+
+        let first_expr = CronFrameExpr::new("0", "0", "1", "*", "*", "*", "*", 0);
+        let second_expr = CronFrameExpr::new("0", "0", "2", "*", "*", "*", "*", 0);
+        let job_ids = |cronframe: &CronFrame| {
+            let cron_jobs = cronframe.jobs().lock().unwrap();
+            let job_id = |schedule: &str| {
+                cron_jobs
+                    .values()
+                    .find(|cron_job| cron_job.schedule() == schedule)
+                    .map(|cron_job| cron_job.id())
+            };
+            (job_id("0 0 1 * * * *"), job_id("0 0 2 * * * *"))
+        };
+
+        // first instance of the framework, the job of the second object changes its schedule
+        let cronframe =
+            CronFrame::with_store(config(), FileStore::new(store_path).unwrap()).unwrap();
+        let first = StoreJobs::new_cron_obj(first_expr.clone()).cf_gather(cronframe.clone());
+        let second = StoreJobs::new_cron_obj(second_expr.clone()).cf_gather(cronframe.clone());
+
+        let (Some(first_id), Some(second_id)) = job_ids(&cronframe) else {
+            panic!("jobs of the two objects not gathered");
+        };
+        let update =
+            format!("http://127.0.0.1:8098/job/my_stored_job/{second_id}/schedset/0 0 3 * * * *");
+        assert!(reqwest::blocking::get(update).is_ok());
+        cronframe.quit();
+        drop((first, second));
+
+        let stored_jobs = FileStore::new(store_path).unwrap().load().unwrap();
+        let stored_jobs: Vec<_> = stored_jobs
+            .iter()
+            .filter(|stored_job| stored_job.name == "my_stored_job")
+            .collect();
+        assert_eq!(stored_jobs.len(), 2);
+
+        // second instance, each object gets back the state of its own job
+        let cronframe =
+            CronFrame::with_store(config(), FileStore::new(store_path).unwrap()).unwrap();
+        let _first = StoreJobs::new_cron_obj(first_expr).cf_gather(cronframe.clone());
+        let _second = StoreJobs::new_cron_obj(second_expr).cf_gather(cronframe.clone());
+
+        {
+            let cron_jobs = cronframe.jobs().lock().unwrap();
+            assert_eq!(cron_jobs[&first_id].schedule(), "0 0 1 * * * *");
+            assert_eq!(cron_jobs[&second_id].schedule(), "0 0 3 * * * *");
+        }
+
+        cronframe.quit();
+    }
+
+    fn stored_global_job() {
+        println!("call from stored global job");
+    }
+
+    #[test]
+    fn job_store_applied_once() {
+        let file_path = "log/job_store_applied_once.log";
+        init_logger(file_path);
+
+        let store_path = "log/job_store_applied_once.json";
+        let _ = fs::remove_file(store_path);
+
+        let stored_id = uuid::Uuid::new_v4();
+        let store = FileStore::new(store_path).unwrap();
+        store
+            .save(&StoredJob {
+                id: stored_id.to_string(),
+                name: "my_stored_global_job".to_string(),
+                job_type: "Global".to_string(),
+                cron_expr: "0 0 4 * * * *".to_string(),
+                timeout: 0,
+                suspended: true,
+                overrides: JobOverrides {
+                    cron_expr: Some("0 0 3 * * * *".to_string()),
+                    ..Default::default()
+                },
+                history: Vec::new(),
+                last_fire: None,
+            })
+            .unwrap();
+
+        let cronframe = CronFrame::with_store(config(), store).unwrap();
+        let add = |cron_expr| {
+            let job =
+                JobBuilder::global_job("my_stored_global_job", stored_global_job, cron_expr, "0")
+                    .build()
+                    .unwrap();
+            let job_id = job.id();
+            cronframe.add_job(job).unwrap();
+            job_id
+        };
+
+        // the stored state is applied when the job is first added, the job takes the stored id
+        let first_id = add("0 0 4 * * * *");
+        // but not when a job with the same name is added again
+        let second_id = add("0 0 5 * * * *");
+        assert_ne!(first_id, stored_id);
+
+        {
+            let cron_jobs = cronframe.jobs().lock().unwrap();
+            assert!(!cron_jobs.contains_key(&first_id));
+            assert_eq!(cron_jobs[&stored_id].schedule(), "0 0 3 * * * *");
+            assert!(cron_jobs[&stored_id].suspended());
+            assert_eq!(cron_jobs[&second_id].schedule(), "0 0 5 * * * *");
+            assert!(!cron_jobs[&second_id].suspended());
+        }
+
+        cronframe.quit();
+    }
+
+    #[test]
+    fn file_store_writes() {
+        let store_path = "log/file_store_writes.json";
+        let _ = fs::remove_file(store_path);
+
+        let stored_job = StoredJob {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: "my_written_job".to_string(),
+            job_type: "Global".to_string(),
+            cron_expr: "0 0 4 * * * *".to_string(),
+            timeout: 0,
+            suspended: false,
+            overrides: Default::default(),
+            history: Vec::new(),
+            last_fire: None,
+        };
+        let run = |run_id: &str| StoredRun {
+            run_id: run_id.to_string(),
+            attempt: 1,
+            fire_time: "2024-01-01T00:00:00+00:00".to_string(),
+            start_time: "2024-01-01T00:00:00+00:00".to_string(),
+            end_time: "2024-01-01T00:00:01+00:00".to_string(),
+            outcome: RunOutcome::Completed,
+            origin: RunOrigin::Scheduled,
+            error: None,
+        };
+
+        let store = FileStore::new(store_path).unwrap();
+        store.save(&stored_job).unwrap();
+
+        // only the latest runs are kept
+        for run_id in ["first", "second", "third"] {
+            store.append_run(&stored_job.id, &run(run_id), 2).unwrap();
+        }
+
+        // saving the state leaves the history as it is
+        store
+            .save_state(&StoredJob {
+                suspended: true,
+                ..stored_job
+            })
+            .unwrap();
+
+        let stored_jobs = FileStore::new(store_path).unwrap().load().unwrap();
+        assert_eq!(stored_jobs.len(), 1);
+        assert!(stored_jobs[0].suspended);
+
+        let run_ids: Vec<_> = stored_jobs[0]
+            .history
+            .iter()
+            .map(|run| run.run_id.as_str())
+            .collect();
+        assert_eq!(run_ids, ["second", "third"]);
+    }
+}
+
+mod catch_up {
+    use crate::{init_logger, job_lines, read_log, test_config, wait_for};
+    use chrono::{Duration, Utc};
+    use cronframe::{
+        cron_impl, cron_obj, mt_job, CatchUpPolicy, CronFrame, CronFrameExpr, FileStore, JobStore,
+        SchedulerConfig, StoredJob,
+    };
+    use std::fs;
+
+    #[cron_obj]
+    #[derive(Debug)]
+    struct CatchUpJobs {
+        expr: CronFrameExpr,
+    }
+
+    #[cron_impl]
+    impl CatchUpJobs {
+        #[mt_job(expr = "expr", catch_up = "FireAll(3)")]
+        fn my_catch_up_all(self) {
+            println!("call from catch up job firing all");
+        }
+
+        // this job follows the policy of the scheduler
+        #[mt_job(expr = "expr")]
+        fn my_catch_up_once(self) {
+            println!("call from catch up job firing once");
+        }
+    }
+
+    #[test]
+    fn catch_up_missed_runs() {
+        let file_path = "log/catch_up_missed_runs.log";
+        init_logger(file_path);
+
+        let store_path = "log/catch_up_missed_runs.json";
+        let _ = fs::remove_file(store_path);
+
+        // both hourly jobs last fired 5 hours ago, before the process went down
+        let store = FileStore::new(store_path).unwrap();
+        let last_fire = Utc::now() - Duration::hours(5);
+        for name in ["my_catch_up_all", "my_catch_up_once"] {
+            store
+                .save(&StoredJob {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    name: name.to_string(),
+                    job_type: "Method".to_string(),
+                    cron_expr: "0 0 * * * * *".to_string(),
+                    timeout: 0,
+                    suspended: false,
+                    overrides: Default::default(),
+                    history: Vec::new(),
+                    last_fire: Some(last_fire.to_rfc3339()),
+                })
+                .unwrap();
+        }
+
+        let config = test_config(SchedulerConfig {
+            catch_up: CatchUpPolicy::FireOnce,
+            ..SchedulerConfig::default()
+        });
+
+        let cronframe = CronFrame::with_store(config, store).unwrap();
+
+        let expr = CronFrameExpr::new("0", "0", "*", "*", "*", "*", "*", 0);
+        let _catch_up_jobs = CatchUpJobs::new_cron_obj(expr).cf_gather(cronframe.clone());
+
+        cronframe.start_scheduler();
+        wait_for(Duration::seconds(3), || {
+            cronframe.jobs().lock().unwrap().values().all(|cron_job| {
+                match cron_job.name().as_str() {
+                    "my_catch_up_all" => cron_job.history().len() >= 3,
+                    "my_catch_up_once" => !cron_job.history().is_empty(),
+                    _ => true,
+                }
+            })
+        });
+        cronframe.quit();
+
+        let file_content = read_log(file_path);
+
+        let executions = |name| {
+            job_lines(&file_content, name)
+                .filter(|line| line.contains("Execution"))
+                .count()
+        };
+
+        assert_eq!(
+            executions("my_catch_up_all"),
+            3,
+            "missed runs not caught up"
+        );
+        assert_eq!(
+            executions("my_catch_up_once"),
+            1,
+            "missed runs not coalesced"
+        );
+        assert!(
+            job_lines(&file_content, "my_catch_up_all")
+                .any(|line| line.contains("Catch Up 3 of") && line.contains("FireAll(3)")),
+            "no catch up in the log file"
+        );
+
+        // the last fire time is kept in the store for the next restart
+        let stored_jobs = FileStore::new(store_path).unwrap().load().unwrap();
+        assert!(stored_jobs
+            .iter()
+            .filter(|stored_job| stored_job.name.starts_with("my_catch_up"))
+            .all(|stored_job| stored_job.last_fire.is_some()));
+    }
+}
+
+mod one_shot {
+    use crate::{job_lines, read_log, test_frame, wait_for};
+    use chrono::{Duration, Utc};
+    use cronframe::{RunOutcome, SchedulerConfig};
+
+    fn my_one_shot_job() {
+        println!("call from one-shot job");
+    }
+
+    fn my_late_one_shot_job() {
+        println!("call from late one-shot job");
+    }
+
+    #[test]
+    fn one_shot_archived() {
+        let file_path = "log/one_shot_archived.log";
+        let cronframe = test_frame(file_path, SchedulerConfig::default());
+
+        // the second job was due before the scheduler started
+        let now = Utc::now();
+        cronframe
+            .clone()
+            .once_job(
+                "my_one_shot_job",
+                my_one_shot_job,
+                now + Duration::seconds(2),
+                "0",
+            )
+            .unwrap()
+            .once_job(
+                "my_late_one_shot_job",
+                my_late_one_shot_job,
+                now - Duration::hours(1),
+                "0",
+            )
+            .unwrap();
+
+        let job_ids: Vec<_> = cronframe
+            .jobs()
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|cron_job| cron_job.trigger().is_once())
+            .map(|cron_job| cron_job.id())
+            .collect();
+        assert_eq!(job_ids.len(), 2);
+
+        cronframe.start_scheduler();
+        wait_for(Duration::seconds(4), || {
+            let cron_jobs = cronframe.jobs().lock().unwrap();
+            !job_ids.iter().any(|job_id| cron_jobs.contains_key(job_id))
+        });
+        cronframe.quit();
+
+        // both jobs ran once and left the job pool, their history is kept
+        for job_id in job_ids {
+            assert!(!cronframe.jobs().lock().unwrap().contains_key(&job_id));
+
+            let history = cronframe.history(job_id);
+            assert_eq!(history.len(), 1);
+            assert_eq!(history[0].outcome, RunOutcome::Completed);
+        }
+
+        let file_content = read_log(file_path);
+
+        for name in ["my_one_shot_job", "my_late_one_shot_job"] {
+            assert!(
+                job_lines(&file_content, name).any(|line| line.contains("Archived")),
+                "no archival in the log file"
+            );
+        }
+    }
+}
+
+mod triggers {
+    use crate::{test_frame, wait_for};
+    use chrono::Duration;
+    use cronframe::{cron_impl, cron_obj, fn_job, mt_job, SchedulerConfig, Trigger};
+
+    #[cron_obj]
+    #[derive(Debug)]
+    struct TriggerJobs;
+
+    #[cron_impl]
+    impl TriggerJobs {
+        #[fn_job(every = "700ms", timeout = "0")]
+        fn my_interval_job() {
+            println!("call from interval job");
+        }
+
+        // each run takes 500ms and the next one is due 500ms after it is over
+        #[mt_job(delay = "500ms")]
+        fn my_delay_job(self) {
+            std::thread::sleep(std::time::Duration::from_millis(500));
+        }
+    }
+
+    fn my_typed_interval_job() {
+        println!("call from typed interval job");
+    }
+
+    #[test]
+    fn interval_and_delay() {
+        let file_path = "log/interval_and_delay.log";
+        let cronframe = test_frame(file_path, SchedulerConfig::default());
+
+        let _trigger_jobs = TriggerJobs::new_cron_obj().cf_gather(cronframe.clone());
+
+        // the same interval as a typed trigger
+        cronframe
+            .clone()
+            .new_job(
+                "my_typed_interval_job",
+                my_typed_interval_job,
+                Trigger::Interval(Duration::milliseconds(700)),
+                "0",
+            )
+            .unwrap();
+
+        cronframe.start_scheduler();
+        wait_for(Duration::seconds(5), || {
+            cronframe.jobs().lock().unwrap().values().all(|cron_job| {
+                match cron_job.name().as_str() {
+                    "my_interval_job" | "my_typed_interval_job" => cron_job.history().len() >= 4,
+                    "my_delay_job" => cron_job.history().len() >= 2,
+                    _ => true,
+                }
+            })
+        });
+        cronframe.quit();
+
+        let jobs: Vec<_> = cronframe
+            .jobs()
+            .lock()
+            .unwrap()
+            .values()
+            .map(|cron_job| (cron_job.name(), cron_job.id(), cron_job.trigger().clone()))
+            .collect();
+        assert!(["my_interval_job", "my_delay_job", "my_typed_interval_job"]
+            .iter()
+            .all(|name| jobs.iter().any(|(job_name, _, _)| job_name == name)));
+
+        for (name, job_id, trigger) in jobs {
+            let history = cronframe.history(job_id);
+            match name.as_str() {
+                // fixed rate, the fire times are an interval apart
+                "my_interval_job" | "my_typed_interval_job" => {
+                    assert_eq!(trigger, Trigger::Interval(Duration::milliseconds(700)));
+                    assert!(history.len() >= 4, "too few interval runs");
+                    for runs in history.windows(2) {
+                        assert_eq!(
+                            runs[1].fire_time - runs[0].fire_time,
+                            Duration::milliseconds(700)
+                        );
+                    }
+                }
+                // fixed delay, a run is due only after the previous one is over
+                "my_delay_job" => {
+                    assert_eq!(trigger.to_string(), "delay 500ms");
+                    assert!(history.len() >= 2, "too few delay runs");
+                    for runs in history.windows(2) {
+                        assert!(
+                            runs[1].fire_time >= runs[0].end_time + Duration::milliseconds(500)
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+mod timezones {
+    use crate::test_frame;
+    use chrono::{TimeZone, Utc};
+    use cronframe::{cron_impl, cron_obj, fn_job, SchedulerConfig, Trigger, Tz};
+
+    #[cron_obj]
+    #[derive(Debug)]
+    struct ZonedJobs;
+
+    #[cron_impl]
+    impl ZonedJobs {
+        #[fn_job(expr = "0 30 2 * * * *", timeout = "0", timezone = "America/New_York")]
+        fn my_zoned_job() {
+            println!("call from zoned job");
+        }
+    }
+
+    #[test]
+    fn dst_transitions() {
+        let rome: Tz = "Europe/Rome".parse().unwrap();
+
+        // 02:30 does not exist on the spring forward day, it fires at 03:30 local time
+        let trigger: Trigger = "0 30 2 * * * *".parse().unwrap();
+        let before = Utc.with_ymd_and_hms(2026, 3, 28, 12, 0, 0).unwrap();
+        let fire_times = trigger.between(
+            &before,
+            &Utc.with_ymd_and_hms(2026, 3, 30, 12, 0, 0).unwrap(),
+            rome,
+        );
+        assert_eq!(
+            fire_times,
+            vec![
+                Utc.with_ymd_and_hms(2026, 3, 29, 1, 30, 0).unwrap(),
+                Utc.with_ymd_and_hms(2026, 3, 30, 0, 30, 0).unwrap(),
+            ]
+        );
+
+        // 02:30 happens twice on the fall back day, it fires at the first one only
+        let before = Utc.with_ymd_and_hms(2026, 10, 24, 12, 0, 0).unwrap();
+        let fire_times = trigger.between(
+            &before,
+            &Utc.with_ymd_and_hms(2026, 10, 26, 12, 0, 0).unwrap(),
+            rome,
+        );
+        assert_eq!(
+            fire_times,
+            vec![
+                Utc.with_ymd_and_hms(2026, 10, 25, 0, 30, 0).unwrap(),
+                Utc.with_ymd_and_hms(2026, 10, 26, 1, 30, 0).unwrap(),
+            ]
+        );
+
+        // with a wildcard hour both occurrences of the repeated hour fire
+        let trigger: Trigger = "0 30 * * * * *".parse().unwrap();
+        let fire_times = trigger.between(
+            &Utc.with_ymd_and_hms(2026, 10, 24, 23, 45, 0).unwrap(),
+            &Utc.with_ymd_and_hms(2026, 10, 25, 2, 0, 0).unwrap(),
+            rome,
+        );
+        assert_eq!(
+            fire_times,
+            vec![
+                Utc.with_ymd_and_hms(2026, 10, 25, 0, 30, 0).unwrap(),
+                Utc.with_ymd_and_hms(2026, 10, 25, 1, 30, 0).unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn job_timezone() {
+        let file_path = "log/job_timezone.log";
+        let cronframe = test_frame(
+            file_path,
+            SchedulerConfig {
+                timezone: Tz::Europe__Rome,
+                ..SchedulerConfig::default()
+            },
+        );
+        ZonedJobs::cf_gather_fn(cronframe.clone());
+
+        let jobs: Vec<_> = cronframe
+            .jobs()
+            .lock()
+            .unwrap()
+            .values()
+            .map(|cron_job| {
+                (
+                    cron_job.name(),
+                    cron_job.timezone(),
+                    cron_job.upcoming_zone(),
+                )
+            })
+            .collect();
+        cronframe.quit();
+
+        // jobs without a zone of their own take the one of the scheduler
+        for (name, timezone, _) in &jobs {
+            let expected = if name == "my_zoned_job" {
+                Tz::America__New_York
+            } else {
+                Tz::Europe__Rome
+            };
+            assert_eq!(*timezone, Some(expected));
+        }
+
+        let (_, _, upcoming) = jobs
+            .iter()
+            .find(|(name, _, _)| name == "my_zoned_job")
+            .expect("zoned job not gathered");
+        let upcoming = upcoming.expect("no upcoming fire time");
+        assert_eq!(upcoming.timezone(), Tz::America__New_York);
+        assert_eq!(upcoming.format("%M:%S").to_string(), "30:00");
+    }
+}
+
+mod manual {
+    use crate::{test_frame, wait_for};
+    use chrono::Duration;
+    use cronframe::{JobBuilder, JobContext, RunOrigin, RunOutcome, SchedulerConfig};
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    static MANUAL_ORIGIN: AtomicBool = AtomicBool::new(false);
+
+    fn my_manual_job(ctx: &JobContext) {
+        MANUAL_ORIGIN.store(ctx.origin() == RunOrigin::Manual, Ordering::SeqCst);
+    }
+
+    #[test]
+    fn manual_trigger() {
+        let file_path = "log/manual_trigger.log";
+        let cronframe = test_frame(file_path, SchedulerConfig::default());
+
+        // a schedule that is never due during the test
+        let job = JobBuilder::function_job_with_context(
+            "my_manual_job",
+            my_manual_job,
+            "0 0 0 1 1 * 2099",
+            "0",
+        )
+        .build()
+        .unwrap();
+        let job_id = job.id();
+        cronframe.add_job(job).unwrap();
+
+        cronframe.start_scheduler();
+        assert!(cronframe.trigger(uuid::Uuid::new_v4()).is_none());
+        let run_id = cronframe.trigger(job_id).expect("manual run not started");
+        wait_for(Duration::seconds(1), || {
+            !cronframe.history(job_id).is_empty()
+        });
+
+        // a suspended job cannot be run manually
+        cronframe
+            .jobs()
+            .lock()
+            .unwrap()
+            .get_mut(&job_id)
+            .unwrap()
+            .suspension(true);
+        assert!(cronframe.trigger(job_id).is_none());
+        cronframe.quit();
+
+        let history = cronframe.history(job_id);
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].run_id, run_id);
+        assert_eq!(history[0].origin, RunOrigin::Manual);
+        assert_eq!(history[0].outcome, RunOutcome::Completed);
+        assert!(MANUAL_ORIGIN.load(Ordering::SeqCst));
+    }
+}
+
+mod removal {
+    use crate::{test_frame, wait_for};
+    use chrono::Duration;
+    use cronframe::{FileStore, JobBuilder, JobStore, SchedulerConfig};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static REMOVED_RUNS: AtomicUsize = AtomicUsize::new(0);
+
+    fn my_removed_job() {
+        REMOVED_RUNS.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn my_kept_job() {}
+
+    #[test]
+    fn remove_jobs() {
+        let store_path = "log/remove_jobs.json";
+        let _ = std::fs::remove_file(store_path);
+
+        let cronframe = test_frame(
+            "log/remove_jobs.log",
+            SchedulerConfig {
+                store: Some(store_path.to_string()),
+                ..SchedulerConfig::default()
+            },
+        );
+
+        let removed =
+            JobBuilder::function_job("my_removed_job", my_removed_job, "* * * * * *", "0")
+                .build()
+                .unwrap();
+        let removed_id = removed.id();
+        cronframe.add_job(removed).unwrap();
+        cronframe
+            .add_job(
+                JobBuilder::function_job("my_kept_job", my_kept_job, "* * * * * *", "0")
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap();
+        cronframe
+            .add_job(
+                JobBuilder::function_job("my_kept_job", my_kept_job, "0 0 0 1 1 * 2099", "0")
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap();
+
+        cronframe.start_scheduler();
+        wait_for(Duration::seconds(2), || {
+            REMOVED_RUNS.load(Ordering::SeqCst) > 0
+        });
+
+        let removed = cronframe.remove_job(removed_id).expect("job not removed");
+        assert_eq!(removed.name(), "my_removed_job");
+        assert!(cronframe.remove_job(removed_id).is_none());
+        let runs = REMOVED_RUNS.load(Ordering::SeqCst);
+
+        // the removed job is no longer scheduled
+        std::thread::sleep(Duration::milliseconds(2000).to_std().unwrap());
+        assert_eq!(REMOVED_RUNS.load(Ordering::SeqCst), runs);
+
+        assert_eq!(cronframe.remove_jobs_by_name("my_kept_job").len(), 2);
+        assert!(cronframe.remove_jobs_by_name("my_kept_job").is_empty());
+        cronframe.quit();
+
+        assert!(!cronframe
+            .jobs()
+            .lock()
+            .unwrap()
+            .values()
+            .any(|cron_job| cron_job.name().starts_with("my_removed")
+                || cron_job.name().starts_with("my_kept")));
+
+        let stored = FileStore::new(store_path).unwrap().load().unwrap();
+        assert!(!stored
+            .iter()
+            .any(|job| job.name == "my_removed_job" || job.name == "my_kept_job"));
+    }
+}
+
+mod build_errors {
+    use cronframe::{CronFrameError, Duration, JobBuilder, Trigger};
+
+    fn my_job() {}
+
+    #[test]
+    fn build_errors() {
+        let error = JobBuilder::global_job("my_job", my_job, "* * * *", "0")
+            .build()
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            CronFrameError::InvalidExpression { ref expression, .. } if expression == "* * * *"
+        ));
+
+        let error = JobBuilder::function_job("my_job", my_job, "every 0s", "0")
+            .build()
+            .unwrap_err();
+        assert!(matches!(error, CronFrameError::InvalidExpression { .. }));
+
+        let error = JobBuilder::function_job_with_trigger(
+            "my_job",
+            my_job,
+            Trigger::Delay(Duration::zero()),
+            "0",
+        )
+        .build()
+        .unwrap_err();
+        assert!(matches!(error, CronFrameError::InvalidExpression { .. }));
+
+        let error = JobBuilder::cli_job("my_job", "* * * * * *", "1s")
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            error,
+            CronFrameError::InvalidTimeout {
+                name: "my_job".to_string(),
+                timeout: "1s".to_string()
+            }
+        );
+
+        for name in ["", " ", "my/job", "my?job"] {
+            let error = JobBuilder::global_job(name, my_job, "* * * * * *", "0")
+                .build()
+                .unwrap_err();
+            assert_eq!(error, CronFrameError::InvalidName(name.to_string()));
+        }
+
+        assert!(
+            JobBuilder::global_job("my_job", my_job, "* * * * * *", "500")
+                .build()
+                .is_ok()
+        );
+    }
+}
+
+mod macro_args {
+    use crate::test_frame;
+    use cronframe::{cron_impl, cron_obj, fn_job, OverlapPolicy, SchedulerConfig, Tz};
+
+    #[cron_obj]
+    #[derive(Debug)]
+    struct KeyedJobs;
+
+    #[cron_impl]
+    impl KeyedJobs {
+        // arguments in any order and without a timeout
+        #[fn_job(
+            tags = ["reports", "nightly"],
+            name = "nightly_export",
+            suspended = true,
+            expr = "0 0 3 * * * *"
+        )]
+        fn my_keyed_job() {
+            println!("call from keyed job");
+        }
+
+        #[fn_job(
+            overlap = "Queue",
+            retries = 2,
+            max_runtime = "30s",
+            every = "90s",
+            timezone = "Asia/Tokyo",
+            timeout = "60000"
+        )]
+        fn my_interval_job() {
+            println!("call from interval job");
+        }
+
+        // durations in days, as accepted by the runtime
+        #[fn_job(delay = "1d", suspended = true)]
+        fn my_daily_delay_job() {
+            println!("call from daily delay job");
+        }
+
+        #[fn_job(expr = "every 2d", suspended = true)]
+        fn my_every_days_job() {
+            println!("call from every days job");
+        }
+    }
+
+    #[test]
+    fn keyed_args() {
+        let file_path = "log/keyed_args.log";
+        let cronframe = test_frame(file_path, SchedulerConfig::default());
+        KeyedJobs::cf_gather_fn(cronframe.clone());
+
+        let jobs = cronframe.jobs().lock().unwrap().clone();
+        cronframe.quit();
+
+        let keyed_job = jobs
+            .values()
+            .find(|cron_job| cron_job.name() == "nightly_export")
+            .unwrap();
+        assert_eq!(keyed_job.tags(), ["reports", "nightly"]);
+        assert!(keyed_job.suspended());
+        assert_eq!(keyed_job.timeout(), None);
+
+        let interval_job = jobs
+            .values()
+            .find(|cron_job| cron_job.name() == "my_interval_job")
+            .unwrap();
+        assert!(interval_job.tags().is_empty());
+        assert!(!interval_job.suspended());
+        assert_eq!(interval_job.schedule(), "every 90s");
+        assert_eq!(interval_job.overlap_policy(), OverlapPolicy::Queue);
+        assert_eq!(interval_job.retry_policy().retries, 2);
+        assert_eq!(
+            interval_job.max_runtime(),
+            Some(cronframe::Duration::seconds(30))
+        );
+        assert_eq!(interval_job.timezone(), Some(Tz::Asia__Tokyo));
+        assert_eq!(
+            interval_job.timeout(),
+            Some(cronframe::Duration::milliseconds(60000))
+        );
+
+        let schedule = |name| {
+            jobs.values()
+                .find(|cron_job| cron_job.name() == name)
+                .unwrap()
+                .schedule()
+        };
+        assert_eq!(schedule("my_daily_delay_job"), "delay 1d");
+        assert_eq!(schedule("my_every_days_job"), "every 2d");
+    }
+}
+
+mod live_state {
+    use crate::{test_frame, wait_for};
+    use chrono::Duration;
+    use cronframe::{cron_impl, cron_obj, mt_job, JobContext, SchedulerConfig};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // runs of the observer job which found the label changed through the handle
+    static OBSERVED: AtomicUsize = AtomicUsize::new(0);
+
+    #[cron_obj]
+    #[derive(Debug)]
+    struct Counter {
+        runs: usize,
+        label: String,
+    }
+
+    #[cron_impl]
+    impl Counter {
+        #[mt_job(every = "500ms")]
+        fn my_counter_job(&mut self) {
+            self.runs += 1;
+            Self::log(self);
+        }
+
+        #[mt_job(every = "500ms")]
+        fn my_observer_job(&self, ctx: &JobContext) -> Result<(), String> {
+            if self.label == "changed" {
+                OBSERVED.fetch_add(1, Ordering::SeqCst);
+            }
+            println!("{} - label {}", ctx.job_name(), self.label);
+            Ok(())
+        }
+
+        fn log(counter: &Counter) {
+            println!("call from counter job, {} runs", counter.runs);
+        }
+    }
+
+    #[test]
+    fn live_state() {
+        let file_path = "log/live_state.log";
+        let cronframe = test_frame(file_path, SchedulerConfig::default());
+        let counter = Counter::new_cron_obj(0, "initial".to_string()).cf_gather(cronframe.clone());
+        counter.lock().unwrap().label = "changed".to_string();
+
+        cronframe.start_scheduler();
+        wait_for(Duration::seconds(3), || {
+            counter.lock().unwrap().runs >= 3 && OBSERVED.load(Ordering::SeqCst) >= 3
+        });
+
+        // the runs of the job mutated the instance held by the handle
+        assert!(counter.lock().unwrap().runs >= 3, "too few counter runs");
+        assert!(
+            OBSERVED.load(Ordering::SeqCst) >= 3,
+            "label change not seen"
+        );
+
+        // the method jobs are dropped along with the handle
+        drop(counter);
+        let dropped = wait_for(Duration::seconds(2), || {
+            !cronframe.jobs().lock().unwrap().values().any(|cron_job| {
+                cron_job.name() == "my_counter_job" || cron_job.name() == "my_observer_job"
+            })
+        });
+        cronframe.quit();
+
+        assert!(dropped, "method jobs not dropped with the handle");
+    }
+}
+
+mod generic_objects {
+    use crate::{test_frame, wait_for};
+    use chrono::Duration;
+    use cronframe::{cron_impl, cron_obj, fn_job, mt_job, CronFrameExpr, SchedulerConfig};
+    use std::fmt::Debug;
+
+    pub trait Source: Clone + Send + 'static {
+        fn read(&self) -> u32;
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct Constant(u32);
+
+    impl Source for Constant {
+        fn read(&self) -> u32 {
+            self.0
+        }
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct Label(&'static str);
+
+    impl Source for Label {
+        fn read(&self) -> u32 {
+            self.0.len() as u32
+        }
+    }
+
+    // generic cron object with a where clause
+    #[cron_obj]
+    struct Poller<T: Source>
+    where
+        T: Debug,
+    {
+        source: T,
+        total: u32,
+    }
+
+    #[cron_impl]
+    impl<T: Source> Poller<T>
+    where
+        T: Debug,
+    {
+        #[mt_job(every = "500ms")]
+        fn my_poller_job(&mut self) {
+            self.total += self.source.read();
+            println!("call from poller job of {:?}", self.source);
+        }
+
+        #[fn_job(expr = "0 0 3 * * * *")]
+        fn my_poller_fn_job() {}
+    }
+
+    // tuple struct, the expression is read from the field at index 1
+    #[cron_obj]
+    struct Pair(u32, CronFrameExpr);
+
+    #[cron_impl]
+    impl Pair {
+        #[mt_job(expr = "1")]
+        fn my_pair_job(&self) {
+            println!("call from pair job {}", self.0);
+        }
+    }
+
+    // cron object with a lifetime, holding function jobs only
+    #[cron_obj]
+    struct Borrowed<'a> {
+        label: &'a str,
+    }
+
+    #[cron_impl]
+    impl<'a> Borrowed<'a> {
+        #[fn_job(expr = "0 0 4 * * * *")]
+        fn my_borrowed_job() {}
+    }
+
+    // cron objects with the same name in different modules
+    mod first {
+        #[cron_obj]
+        pub struct Same {
+            pub value: u32,
+        }
+    }
+
+    mod second {
+        #[cron_obj]
+        pub struct Same {
+            pub value: u32,
+        }
+    }
+
+    #[cron_impl]
+    impl first::Same {
+        #[fn_job(expr = "0 0 5 * * * *")]
+        fn my_first_same_job() {}
+    }
+
+    #[cron_impl]
+    impl second::Same {
+        #[fn_job(expr = "0 0 6 * * * *")]
+        fn my_second_same_job() {}
+    }
+
+    #[test]
+    fn generic_objects() {
+        let file_path = "log/generic_objects.log";
+        let cronframe = test_frame(file_path, SchedulerConfig::default());
+        let poller = Poller::new_cron_obj(Constant(2), 0).cf_gather(cronframe.clone());
+        let labelled = Poller::new_cron_obj(Label("abc"), 0).cf_gather(cronframe.clone());
+        let expr = CronFrameExpr::new("*", "*", "*", "*", "*", "*", "*", 0);
+        let _pair = Pair::new_cron_obj(7, expr).cf_gather(cronframe.clone());
+        Borrowed::cf_gather_fn(cronframe.clone());
+        first::Same::cf_gather_fn(cronframe.clone());
+        second::Same::cf_gather_fn(cronframe.clone());
+
+        let borrowed = Borrowed::new_cron_obj("borrowed");
+        assert_eq!(borrowed.label, "borrowed");
+        assert_eq!(first::Same::new_cron_obj(1).value, 1);
+        assert_eq!(second::Same::new_cron_obj(2).value, 2);
+
+        cronframe.start_scheduler();
+        wait_for(Duration::seconds(3), || {
+            poller.lock().unwrap().total >= 6 && labelled.lock().unwrap().total >= 9
+        });
+
+        let names = || -> Vec<_> {
+            cronframe
+                .jobs()
+                .lock()
+                .unwrap()
+                .values()
+                .map(|cron_job| cron_job.name())
+                .collect()
+        };
+        let count = |names: &[String], name| names.iter().filter(|job| *job == name).count();
+        let gathered = names();
+        let total = poller.lock().unwrap().total;
+        let labelled_total = labelled.lock().unwrap().total;
+
+        // each instantiation of the generic cron object drops its own function jobs
+        Poller::<Label>::cf_drop_fn();
+        wait_for(Duration::seconds(5), || {
+            count(&names(), "my_poller_fn_job") == 1
+        });
+        let remaining = names();
+        cronframe.quit();
+
+        // both instantiations gathered their function jobs
+        assert_eq!(count(&gathered, "my_poller_job"), 2);
+        assert_eq!(count(&gathered, "my_poller_fn_job"), 2);
+        assert_eq!(count(&remaining, "my_poller_fn_job"), 1);
+
+        // the jobs of both same-named cron objects have been gathered
+        for name in [
+            "my_poller_job",
+            "my_poller_fn_job",
+            "my_pair_job",
+            "my_borrowed_job",
+            "my_first_same_job",
+            "my_second_same_job",
+        ] {
+            assert!(gathered.iter().any(|job| job == name), "missing job {name}");
+        }
+        assert!(total >= 6, "too few poller runs");
+        assert!(labelled_total >= 9, "too few labelled poller runs");
+    }
+}